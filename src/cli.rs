@@ -1,4 +1,22 @@
-use clap::Parser;
+use clap::{ArgGroup, Parser};
+
+use crate::csv_input::InputFormat;
+use crate::error::ErrorFormat;
+use crate::format::{ColorChoice, OutputField, OutputFormat, SortKey};
+use crate::parse::ArrayMode;
+use crate::syntax::{JsonType, NormalizationForm, NumberKind};
+
+/// Parses a probability between 0.0 and 1.0.
+fn parse_fraction(fraction: &str) -> Result<f64, String> {
+    let fraction: f64 = fraction
+        .parse()
+        .map_err(|e: std::num::ParseFloatError| e.to_string())?;
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(format!("{} is not between 0.0 and 1.0", fraction))
+    }
+}
 
 #[derive(Parser)]
 #[clap(
@@ -15,6 +33,17 @@ use clap::Parser;
                 srch numericValue \">50<100\" data.json -n\t# Numeric search"
 )]
 #[command(version)]
+// The search modes decide how a value matches, so combining them would silently ignore all but one
+#[command(group(
+    ArgGroup::new("search_mode")
+        .multiple(false)
+        .args([
+            "numeric_search", "exists", "eq", "fuzzy", "glob", "fixed_strings", "script",
+            "concat_search", "value_list", "has_non_ascii", "jq_value", "contains", "equals",
+            "int_range", "divisible_by", "odd", "even", "object_size", "semver", "epoch",
+            "epoch_ms", "compare", "flatten",
+        ])
+))]
 pub struct Cli {
     #[clap(
         short = 'j',
@@ -26,17 +55,20 @@ pub struct Cli {
 
     #[clap(
         value_name = "SEARCH_PATH",
+        required_unless_present_any = ["flatten", "field"],
         help = "Search path in the format 'fieldPath.fieldName'.\n\
                                          - fieldPath: Path to the field, separated by the FIELD_PATH_SEPARATOR (default .) (e.g., 'topLevel.nestedField' or just 'field').\n\
-                                         - fieldName: Name of the field to search for at the end of the path."
+                                         - fieldName: Name of the field to search for at the end of the path.\n\
+                                         Omitted with --flatten and --field."
     )]
-    pub search_path: String,
+    pub search_path: Option<String>,
 
     #[clap(
         value_name = "SEARCH_TERM",
-        help = "Regex to compare values against. The values are compared as strings."
+        required_unless_present_any = ["exists", "compare", "flatten", "object_size", "divisible_by", "odd", "even", "value_list", "field", "script", "has_non_ascii", "jq_value", "contains", "int_range", "equals"],
+        help = "Regex to compare values against. The values are compared as strings. Omitted with --exists, --compare, --flatten, --object-size, --divisible-by, --odd, --even, --value-list, --script, --has-non-ascii, --jq-value, --contains, --int-range and --equals."
     )]
-    pub search_term: String,
+    pub search_term: Option<String>,
 
     #[clap(value_name = "JSON_FILES", num_args = 0.., help = "Paths to JSON files to search within. If provided, srch will search these files instead of stdin or --json-string.\n\
                                                                 Example: example_files/*.json")]
@@ -60,7 +92,8 @@ pub struct Cli {
         short = 'f',
         long = "field-path-separator",
         help = "Separator for the field path. Applies both to the input path as well as the output paths.",
-        default_value = "."
+        default_value = ".",
+        env = "SRCH_SEPARATOR"
     )]
     pub field_path_separator: String,
 
@@ -77,30 +110,1000 @@ pub struct Cli {
         help = "Treat the search term as a numeric comparison (e.g., '>25', '<=10', or ranges like '>10<20')."
     )]
     pub numeric_search: bool,
+
+    #[clap(
+        long = "root",
+        value_name = "ROOT_PATH",
+        help = "Start the search at this subtree (e.g., 'a.b'), separated by the FIELD_PATH_SEPARATOR. Result paths are relative to it."
+    )]
+    pub root: Option<String>,
+
+    #[clap(
+        long = "coerce-strings",
+        help = "In numeric search, also compare string values that parse as numbers (e.g., \"30\")."
+    )]
+    pub coerce_strings: bool,
+
+    #[clap(
+        long = "exclude-path",
+        value_name = "EXCLUDE_PATH",
+        help = "Skip this subtree (e.g., 'debug'), separated by the FIELD_PATH_SEPARATOR. '*' matches any single segment. Can be given multiple times."
+    )]
+    pub exclude_paths: Vec<String>,
+
+    #[clap(
+        long = "table",
+        help = "Print results as a table with aligned path and value columns."
+    )]
+    pub table: bool,
+
+    #[clap(
+        long = "max-value-width",
+        value_name = "WIDTH",
+        help = "Truncate values in table output to this many characters (0 disables truncation).",
+        default_value_t = 50,
+        env = "SRCH_MAX_VALUE_WIDTH"
+    )]
+    pub max_value_width: usize,
+
+    #[clap(
+        long = "replace",
+        value_name = "REPLACEMENT",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "fuzzy", "value_list", "compare", "flatten", "object_size", "divisible_by", "odd", "even", "has_non_ascii", "jq_value", "contains", "int_range", "equals", "semver", "epoch", "epoch_ms"],
+        help = "Replace regex matches in the matched string values and print the whole modified JSON instead of the results. Supports capture groups like '$1'. Not available in the search modes without a regex, e.g. --eq or --exists."
+    )]
+    pub replace: Option<String>,
+
+    #[clap(
+        long = "delete",
+        conflicts_with = "replace",
+        help = "Remove the matched fields and array elements and print the whole modified JSON instead of the results."
+    )]
+    pub delete: bool,
+
+    #[clap(
+        long = "all",
+        value_name = "NUMERIC_TERM",
+        help = "Additional numeric comparison (e.g., '<65') that must hold as well as SEARCH_TERM. Can be given multiple times. Requires numeric search, with -n or a 'num:' SEARCH_TERM."
+    )]
+    pub all_numeric_terms: Vec<String>,
+
+    #[clap(
+        long = "error-format",
+        value_name = "FORMAT",
+        help = "Format of error messages on stderr. 'json' prints objects like {\"error\":\"...\",\"kind\":\"json_parse\",\"file\":\"...\"}.",
+        value_enum,
+        default_value_t = ErrorFormat::Text,
+        env = "SRCH_ERROR_FORMAT"
+    )]
+    pub error_format: ErrorFormat,
+
+    #[clap(
+        long = "keys-and-values",
+        help = "Match the regex against both the key names and the values of all fields under SEARCH_PATH. Results are marked with [key] or [value]."
+    )]
+    pub keys_and_values: bool,
+
+    #[clap(
+        long = "max-array-depth",
+        value_name = "DEPTH",
+        help = "Do not search arrays nested inside DEPTH or more arrays (0 skips all arrays)."
+    )]
+    pub max_array_depth: Option<usize>,
+
+    #[clap(
+        long = "max-object-depth",
+        value_name = "DEPTH",
+        help = "Do not search objects nested inside DEPTH or more objects (0 skips all objects)."
+    )]
+    pub max_object_depth: Option<usize>,
+
+    #[clap(
+        long = "annotate-types",
+        help = "Annotate each segment of the result paths with its JSON type, e.g. 'users(array).0(object).name(string)'."
+    )]
+    pub annotate_types: bool,
+
+    #[clap(
+        long = "timeout",
+        value_name = "SECONDS",
+        help = "Abort with an error if the run takes longer than this. Results found so far are still printed.",
+        env = "SRCH_TIMEOUT"
+    )]
+    pub timeout: Option<f64>,
+
+    #[clap(
+        long = "number-kind",
+        value_name = "KIND",
+        value_enum,
+        help = "Only match JSON numbers of this kind. 'int' excludes values written with a fraction or exponent like 30.0."
+    )]
+    pub number_kind: Option<NumberKind>,
+
+    #[clap(
+        long = "exists",
+        help = "Report every occurrence of the field regardless of its value. SEARCH_TERM is omitted, so the second argument is the first JSON file."
+    )]
+    pub exists: bool,
+
+    #[clap(
+        long = "line-numbers",
+        help = "Prefix each output line with its number, e.g. '1: path: value'."
+    )]
+    pub line_numbers: bool,
+
+    #[clap(
+        long = "line-numbers-per-file",
+        requires = "line_numbers",
+        help = "Restart the line numbering for each JSON file."
+    )]
+    pub line_numbers_per_file: bool,
+
+    #[clap(
+        long = "captures",
+        conflicts_with_all = ["numeric_search", "exists"],
+        help = "Print the named capture groups of the regex as a JSON object instead of the matched value, e.g. '(?P<user>\\w+)-(?P<id>\\d+)'."
+    )]
+    pub captures: bool,
+
+    #[clap(
+        short = 'g',
+        long = "glob",
+        conflicts_with = "numeric_search",
+        help = "Interpret SEARCH_TERM as a shell glob matching the whole value, e.g. 'user_*'. '*' matches any characters and '?' a single one."
+    )]
+    pub glob: bool,
+
+    #[clap(
+        short = 'F',
+        long = "fixed-strings",
+        conflicts_with_all = ["numeric_search", "glob", "script", "eq"],
+        help = "Interpret SEARCH_TERM as a literal string instead of a regex, e.g. '1.5' does not match \"135\"."
+    )]
+    pub fixed_strings: bool,
+
+    #[clap(
+        short = 'w',
+        long = "whole-value",
+        conflicts_with_all = ["numeric_search", "glob", "script", "eq"],
+        help = "Require SEARCH_TERM to match the whole value, e.g. 'test' does not match \"mytestvalue\". Strings match without their quotes, numbers by their JSON text."
+    )]
+    pub whole_value: bool,
+
+    #[clap(
+        long = "fuzzy",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "fixed_strings", "whole_value", "script", "concat_search", "captures", "count_matches"],
+        help = "Match strings within --max-distance edits of SEARCH_TERM instead of using a regex, e.g. 'Maxx' matches \"Max\". Other values never match."
+    )]
+    pub fuzzy: bool,
+
+    #[clap(
+        long = "max-distance",
+        value_name = "N",
+        default_value_t = 2,
+        requires = "fuzzy",
+        help = "Maximum Levenshtein distance for --fuzzy, counting inserted, deleted and replaced characters."
+    )]
+    pub max_distance: usize,
+
+    #[clap(
+        long = "sort",
+        value_name = "KEY",
+        value_enum,
+        conflicts_with_all = ["count_distinct", "files_with_matches", "uniq", "count_runs", "file_header", "replace", "delete"],
+        help = "Sort the matches of each input by their path, their value as text or their numeric value, with the other values after the numbers in text order. The matches are held in memory until all inputs are searched, see --max-results."
+    )]
+    pub sort: Option<SortKey>,
+
+    #[clap(
+        long = "sort-global",
+        requires = "sort",
+        help = "With --sort, sort the matches across all inputs instead of per input."
+    )]
+    pub sort_global: bool,
+
+    #[clap(
+        long = "context",
+        conflicts_with_all = ["table", "output", "tree", "files_with_matches", "count_distinct", "replace", "delete"],
+        help = "Print the object containing each match pretty-printed on indented lines below it."
+    )]
+    pub context: bool,
+
+    #[clap(
+        long = "context-depth",
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "context",
+        help = "With --context, print the value N levels above each match instead, e.g. 2 for the array holding the containing object. Levels above the root print the whole document."
+    )]
+    pub context_depth: u64,
+
+    #[clap(
+        long = "parse-embedded",
+        help = "Also search string values containing a JSON object or array. The path segment of such a string is marked with '~', e.g. 'payload~.id'."
+    )]
+    pub parse_embedded: bool,
+
+    #[clap(
+        long = "head",
+        value_name = "N",
+        conflicts_with = "tail",
+        help = "Only search the first N elements of every array."
+    )]
+    pub head: Option<usize>,
+
+    #[clap(
+        long = "tail",
+        value_name = "N",
+        help = "Only search the last N elements of every array."
+    )]
+    pub tail: Option<usize>,
+
+    #[clap(
+        long = "output",
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        conflicts_with = "table",
+        help = "Output format. 'json' and 'yaml' print all results as one list of records with the file, path and value. 'sql' prints one INSERT statement with the path and value per result into the --sql-table; add the file with --fields. 'properties' prints 'path=value' lines of a Java properties file."
+    )]
+    pub output: OutputFormat,
+
+    #[clap(
+        long = "normalize",
+        value_enum,
+        help = "Apply Unicode normalization to string values and SEARCH_TERM before matching, so composed and decomposed characters match each other. Other values are unaffected."
+    )]
+    pub normalize: Option<NormalizationForm>,
+
+    #[clap(
+        long = "rel-epsilon",
+        value_name = "P",
+        default_value_t = 0.0,
+        help = "Relative tolerance for '==' in numeric search, with -n or a 'num:' SEARCH_TERM. Numbers match if they differ by at most P times the larger magnitude, e.g. 0.001 for 0.1%."
+    )]
+    pub rel_epsilon: f64,
+
+    #[clap(
+        long = "collapse-indices",
+        conflicts_with_all = ["table", "output", "path_output"],
+        help = "Print each distinct path once, with array indices replaced by '[]', e.g. 'users[].name'."
+    )]
+    pub collapse_indices: bool,
+
+    #[clap(
+        long = "include-stdin",
+        conflicts_with = "json_string",
+        help = "Also search stdin if it is piped when JSON files are given. Its results are reported first, as '<stdin>'."
+    )]
+    pub include_stdin: bool,
+
+    #[clap(
+        long = "concat-search",
+        conflicts_with_all = ["numeric_search", "exists", "captures"],
+        help = "Match the regex against all string values under the field, joined by spaces, and report the whole field on a match."
+    )]
+    pub concat_search: bool,
+
+    #[clap(
+        long = "eq",
+        conflicts_with_all = ["numeric_search", "exists", "captures", "glob", "concat_search"],
+        help = "Match only values exactly equal to SEARCH_TERM instead of using a regex. Numbers and booleans are compared by their JSON text."
+    )]
+    pub eq: bool,
+
+    #[clap(
+        long = "max-file-size",
+        value_name = "BYTES",
+        help = "Skip JSON files larger than BYTES with a warning instead of loading them into memory."
+    )]
+    pub max_file_size: Option<u64>,
+
+    #[clap(
+        long = "array-mode",
+        value_enum,
+        default_value_t = ArrayMode::All,
+        help = "Which array elements with matches are reported: the first, all, or the last one of every array."
+    )]
+    pub array_mode: ArrayMode,
+
+    #[clap(
+        long = "typed-values",
+        help = "Prefix each printed value with its JSON type, e.g. 'string:\"active\"' or 'number:30'."
+    )]
+    pub typed_values: bool,
+
+    #[clap(
+        long = "tree",
+        conflicts_with_all = ["table", "output", "collapse_indices", "path_output"],
+        help = "Print all results as a tree with shared path prefixes merged and the values at the leaves."
+    )]
+    pub tree: bool,
+
+    #[clap(
+        long = "aggregate",
+        num_args = 2,
+        value_names = ["FUNCTION", "FIELDS"],
+        conflicts_with_all = ["numeric_search", "keys_and_values", "exists", "eq"],
+        help = "Match objects under the search path whose comma-separated FIELDS combined with FUNCTION (sum, min or max) satisfy the numeric SEARCH_TERM, e.g. '--aggregate sum a,b orders \">100\"'. Objects with a missing or non-numeric field are skipped."
+    )]
+    pub aggregate: Option<Vec<String>>,
+
+    #[clap(
+        short = 'l',
+        long = "files-with-matches",
+        conflicts_with_all = ["table", "output", "tree", "collapse_indices"],
+        help = "Only print the name of each JSON file containing a match. Each file is searched until its first match."
+    )]
+    pub files_with_matches: bool,
+
+    #[clap(
+        long = "first-file",
+        help = "Stop after the first JSON file containing a match."
+    )]
+    pub first_file: bool,
+
+    #[clap(
+        long = "not-type",
+        value_name = "TYPE",
+        value_enum,
+        help = "Skip values of this JSON type. Combine with --exists to find every value that is not of the type. Can be given multiple times."
+    )]
+    pub not_types: Vec<JsonType>,
+
+    #[clap(
+        long = "path-filter",
+        value_name = "REGEX",
+        help = "Only keep matches whose full path, joined by the FIELD_PATH_SEPARATOR, matches this regex, e.g. 'billing'."
+    )]
+    pub path_filter: Option<String>,
+
+    #[clap(
+        long = "path-regex",
+        value_name = "REGEX",
+        help = "Only compare the values of fields whose full path, joined by the FIELD_PATH_SEPARATOR, matches this regex, e.g. 'users'. Unlike --path-filter, other fields are skipped before their value is compared."
+    )]
+    pub path_regex: Option<String>,
+
+    #[clap(
+        long = "siblings",
+        conflicts_with_all = ["table", "output", "tree", "collapse_indices", "files_with_matches"],
+        help = "Print the other keys of the object containing each match on indented lines below it."
+    )]
+    pub siblings: bool,
+
+    #[clap(
+        long = "sibling-values",
+        requires = "siblings",
+        help = "Print the siblings as 'key: value' instead of only their keys."
+    )]
+    pub sibling_values: bool,
+
+    #[clap(
+        long = "compare",
+        num_args = 3,
+        value_names = ["FIELD_A", "OPERATOR", "FIELD_B"],
+        conflicts_with_all = ["numeric_search", "keys_and_values", "exists", "eq", "aggregate"],
+        help = "Match objects under the search path whose numeric FIELD_A compares to FIELD_B with OPERATOR (<, <=, >, >= or ==), e.g. '--compare end \"<\" start'. SEARCH_TERM is omitted. Objects with a missing or non-numeric field are skipped."
+    )]
+    pub compare: Option<Vec<String>>,
+
+    #[clap(
+        long = "flatten",
+        conflicts_with_all = ["numeric_search", "keys_and_values", "exists", "eq", "aggregate", "compare", "replace", "delete"],
+        help = "Print every primitive value of the document as 'path: value'. SEARCH_PATH and SEARCH_TERM are omitted."
+    )]
+    pub flatten: bool,
+
+    #[clap(
+        long = "semver",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten"],
+        help = "Compare string values as semantic versions, e.g. '\">=1.2.0\"' matches \"1.10.0\". Without an operator, the versions must be equal. Values that are not valid versions never match."
+    )]
+    pub semver: bool,
+
+    #[clap(
+        long = "epoch",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver"],
+        help = "Compare numbers as Unix timestamps in seconds against the date in SEARCH_TERM, e.g. '\">=2024-01-01\"'. Dates are days, which start at local midnight, or RFC 3339 timestamps. Without an operator, the timestamp must be exactly the date."
+    )]
+    pub epoch: bool,
+
+    #[clap(
+        long = "epoch-ms",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "epoch"],
+        help = "Like --epoch, with the timestamps in milliseconds."
+    )]
+    pub epoch_ms: bool,
+
+    #[clap(
+        long = "count-distinct",
+        conflicts_with_all = ["files_with_matches", "table", "tree", "output", "replace", "delete"],
+        help = "Print the number of distinct matched values instead of the matches, per file and in total. Values are compared by their JSON text."
+    )]
+    pub count_distinct: bool,
+
+    #[clap(
+        long = "object-size",
+        value_name = "SIZE_TERM",
+        allow_hyphen_values = true,
+        conflicts_with_all = ["numeric_search", "keys_and_values", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver"],
+        help = "Match objects at the search path whose number of keys satisfies the numeric term, e.g. '\">5\"' or '\">=2<10\"'. SEARCH_TERM is omitted. Values that are not objects never match."
+    )]
+    pub object_size: Option<String>,
+
+    #[clap(
+        long = "uniq",
+        conflicts_with_all = ["files_with_matches", "count_distinct", "table", "tree", "output", "replace", "delete"],
+        help = "Like the uniq command, print only the first of consecutive matches with the same value, in output order."
+    )]
+    pub uniq: bool,
+
+    #[clap(
+        long = "count-runs",
+        requires = "uniq",
+        help = "With --uniq, prefix each printed match with the number of consecutive matches it stands for."
+    )]
+    pub count_runs: bool,
+
+    #[clap(
+        long = "explain",
+        help = "Print how the search path and term were parsed (path parts, field name, numeric term and regex), then exit without searching."
+    )]
+    pub explain: bool,
+
+    #[clap(
+        long = "read-timeout",
+        value_name = "MS",
+        help = "Fail reading stdin or a file (e.g., a named pipe) if it does not reach its end within MS milliseconds."
+    )]
+    pub read_timeout: Option<u64>,
+
+    #[clap(
+        long = "key-by",
+        value_name = "KEY_FIELD",
+        conflicts_with_all = ["keys_and_values", "aggregate", "compare", "flatten", "table", "tree", "files_with_matches", "count_distinct", "uniq", "replace", "delete"],
+        help = "Print one JSON object mapping the KEY_FIELD value of each match's enclosing object to the matched value, e.g. '--key-by id'. Matches without KEY_FIELD are skipped. For duplicate keys, the last match wins."
+    )]
+    pub key_by: Option<String>,
+
+    #[clap(
+        long = "base64-decode",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size"],
+        help = "Search the base64-decoded content of string values. Invalid UTF-8 is replaced, and values that are not valid base64 never match."
+    )]
+    pub base64_decode: bool,
+
+    #[clap(
+        long = "show-decoded",
+        requires = "base64_decode",
+        help = "With --base64-decode, print the decoded value instead of the original."
+    )]
+    pub show_decoded: bool,
+
+    #[clap(
+        long = "divisible-by",
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "base64_decode", "odd", "even"],
+        help = "Match integers divisible by N. SEARCH_TERM is omitted. Non-integers such as 4.5 or 4.0 never match."
+    )]
+    pub divisible_by: Option<u64>,
+
+    #[clap(
+        long = "odd",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "base64_decode", "even"],
+        help = "Match odd integers. SEARCH_TERM is omitted."
+    )]
+    pub odd: bool,
+
+    #[clap(
+        long = "even",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "base64_decode"],
+        help = "Match even integers. SEARCH_TERM is omitted."
+    )]
+    pub even: bool,
+
+    #[clap(
+        long = "strip-prefix",
+        conflicts_with_all = ["replace", "delete"],
+        help = "Print result paths without the fieldPath part of SEARCH_PATH, e.g. '0.city' instead of 'users.address.0.city' for 'users.address.city'. Array indices and keys below it are kept."
+    )]
+    pub strip_prefix: bool,
+
+    #[clap(
+        long = "count-matches",
+        conflicts_with_all = ["captures", "numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "replace", "delete"],
+        help = "Print the number of non-overlapping regex matches within each matching value instead of the value, e.g. 'msg: 3'."
+    )]
+    pub count_matches: bool,
+
+    #[clap(
+        long = "field",
+        value_name = "NAME",
+        conflicts_with = "flatten",
+        help = "Name of the field to search for, instead of SEARCH_PATH. The name is taken as is, even if it contains the FIELD_PATH_SEPARATOR."
+    )]
+    pub field: Option<String>,
+
+    #[clap(
+        long = "in-path",
+        value_name = "PATH",
+        requires = "field",
+        help = "With --field, path to the field, separated by the FIELD_PATH_SEPARATOR (e.g., 'topLevel.nestedField')."
+    )]
+    pub in_path: Option<String>,
+
+    #[clap(
+        long = "infer-types",
+        conflicts_with_all = ["typed_values", "hide_value", "output", "key_by", "replace", "delete"],
+        help = "Print the inferred type of each matched value instead of the value: string, integer, number, boolean, null, object or array<...>. Combine with --collapse-indices for a compact schema sketch."
+    )]
+    pub infer_types: bool,
+
+    #[clap(
+        long = "field-ignore-case",
+        help = "Match the field name at the end of the search path case-insensitively, e.g. 'name' finds 'Name'. The field path is still matched exactly."
+    )]
+    pub field_ignore_case: bool,
+
+    #[clap(
+        long = "sql-table",
+        value_name = "TABLE",
+        default_value = "matches",
+        help = "Table name of the INSERT statements for '--output sql'. It is printed as given, so quote it yourself if needed."
+    )]
+    pub sql_table: String,
+
+    #[clap(
+        long = "value-list",
+        value_name = "PATH",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "base64_decode", "count_matches", "captures"],
+        help = "Match values listed in the file, one per line. Strings are compared by their content, other values by their JSON text. SEARCH_TERM is omitted."
+    )]
+    pub value_list: Option<String>,
+
+    #[clap(
+        short = 'v',
+        long = "invert-match",
+        conflicts_with_all = ["exists", "aggregate", "compare", "flatten", "object_size", "keys_and_values", "captures", "count_matches", "replace", "delete"],
+        help = "Match the primitive values at the search path that do not match the search term, e.g. values not in the --value-list."
+    )]
+    pub invert_match: bool,
+
+    #[clap(
+        long = "show-len",
+        conflicts_with_all = ["hide_value", "table", "tree", "output", "files_with_matches", "count_distinct", "key_by"],
+        help = "Follow each printed value with its length in bytes and characters, e.g. '(7 bytes, 5 chars)'. Strings are measured by their content, other values by their JSON text."
+    )]
+    pub show_len: bool,
+
+    #[clap(
+        long = "sample",
+        value_name = "FRACTION",
+        value_parser = parse_fraction,
+        conflicts_with_all = ["files_with_matches", "replace", "delete"],
+        help = "Keep each match with the probability FRACTION (0.0 to 1.0), e.g. '--sample 0.01' for about one percent of the matches."
+    )]
+    pub sample: Option<f64>,
+
+    #[clap(
+        long = "seed",
+        requires = "sample",
+        help = "Seed for --sample, so that repeated runs keep the same matches."
+    )]
+    pub seed: Option<u64>,
+
+    #[clap(
+        long = "raw",
+        conflicts_with_all = ["captures", "count_matches", "show_decoded", "output", "replace", "delete"],
+        help = "Print matched numbers, strings, booleans and nulls exactly as written in the input, e.g. '2.50' instead of '2.5'."
+    )]
+    pub raw: bool,
+
+    #[clap(
+        long = "script",
+        value_name = "NAME",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list"],
+        help = "Match string values containing a character of the Unicode script NAME, e.g. 'Cyrillic' or 'Han'. Shorthand for the search term '\\p{Script=NAME}', so SEARCH_TERM is omitted."
+    )]
+    pub script: Option<String>,
+
+    #[clap(
+        long = "has-non-ascii",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list", "script", "base64_decode", "count_matches", "captures"],
+        help = "Match string values containing any non-ASCII character. SEARCH_TERM is omitted."
+    )]
+    pub has_non_ascii: bool,
+
+    #[clap(
+        long = "match-array-elements",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list", "has_non_ascii", "captures", "count_matches"],
+        help = "Match an array of scalars at the search path if any of its elements matches the search term. The whole array is reported."
+    )]
+    pub match_array_elements: bool,
+
+    #[clap(
+        long = "all-elements",
+        requires = "match_array_elements",
+        help = "With --match-array-elements, require every element of a non-empty array to match."
+    )]
+    pub all_elements: bool,
+
+    #[clap(
+        long = "no-config",
+        help = "Ignore the config file './.srch.toml' or '~/.config/srch/config.toml', which sets defaults for long options, e.g. 'field-path-separator = \"/\"'."
+    )]
+    pub no_config: bool,
+
+    #[clap(
+        long = "watch",
+        conflicts_with_all = ["json_string", "include_stdin", "replace", "delete", "explain"],
+        help = "Search the JSON files again whenever one of them changes, clearing the screen before each search. Runs until interrupted."
+    )]
+    pub watch: bool,
+
+    #[clap(
+        long = "watch-append",
+        requires = "watch",
+        help = "With --watch, append the results of each search instead of clearing the screen."
+    )]
+    pub watch_append: bool,
+
+    #[clap(
+        long = "parse-number",
+        help = "In numeric search, also compare strings formatted as currency or percentages, e.g. \"$1,200.50\" or \"45%\" (0.45). Strings that do not parse do not match."
+    )]
+    pub parse_number: bool,
+
+    #[clap(
+        long = "canonical-numbers",
+        help = "Before regex matching, render floats in their shortest form without a trailing '.0' or exponent, so '30.0' and '3e1' match '^30$' like '30', and '1.50' is matched as '1.5'."
+    )]
+    pub canonical_numbers: bool,
+
+    #[clap(
+        long = "coerce-numeric-strings",
+        help = "Before regex matching, render string values that hold only a number like that number in its shortest form, so '\"30\"' and '\"30.0\"' match '^30$' like 30. Other strings are matched quoted as before."
+    )]
+    pub coerce_numeric_strings: bool,
+
+    #[clap(
+        long = "fail-on-match",
+        conflicts_with_all = ["replace", "delete", "watch", "explain"],
+        help = "Exit with code 1 if any match is found and 0 otherwise, e.g. to assert in CI that no secrets are present. Errors exit with code 2."
+    )]
+    pub fail_on_match: bool,
+
+    #[clap(
+        short = 'q',
+        long = "quiet",
+        requires = "fail_on_match",
+        help = "With --fail-on-match, print no matches and stop searching each file at its first match."
+    )]
+    pub quiet: bool,
+
+    #[clap(
+        long = "input",
+        value_enum,
+        default_value_t = InputFormat::Json,
+        conflicts_with_all = ["replace", "delete"],
+        help = "Input format. 'csv' searches the JSON in the --json-column of each row, prefixing the result paths with the row number, starting at 1 after the header."
+    )]
+    pub input: InputFormat,
+
+    #[clap(
+        long = "json-column",
+        value_name = "NAME",
+        required_if_eq("input", "csv"),
+        requires = "input",
+        help = "Name of the CSV column holding JSON, for '--input csv'."
+    )]
+    pub json_column: Option<String>,
+
+    #[clap(
+        long = "max-per-file",
+        value_name = "N",
+        conflicts_with_all = ["replace", "delete"],
+        help = "Keep at most the first N matches of each file."
+    )]
+    pub max_per_file: Option<usize>,
+
+    #[clap(
+        long = "also",
+        num_args = 2,
+        value_names = ["FORMAT", "PATH"],
+        conflicts_with_all = ["replace", "delete", "watch", "explain"],
+        help = "Also write the results to the file PATH in the structured FORMAT 'json', 'yaml', 'sql' or 'properties', e.g. '--also json results.json'."
+    )]
+    pub also: Option<Vec<String>>,
+
+    #[clap(
+        long = "jq-value",
+        value_name = "EXPR",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list", "script", "has_non_ascii", "match_array_elements", "count_matches", "captures"],
+        help = "Match scalar values for which the jq-like expression EXPR is true, e.g. '. > 30 and . < 65'. Supports '.', JSON literals, '==', '!=', '<', '<=', '>', '>=', 'and', 'or' and parentheses. SEARCH_TERM is omitted."
+    )]
+    pub jq_value: Option<String>,
+
+    #[clap(
+        long = "contains",
+        value_name = "VALUE",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list", "script", "has_non_ascii", "match_array_elements", "jq_value", "count_matches", "captures"],
+        help = "Match arrays containing an element equal to VALUE. VALUE is parsed as JSON, e.g. '7' or 'true', and otherwise taken as a string, e.g. 'urgent'. SEARCH_TERM is omitted."
+    )]
+    pub contains: Option<String>,
+
+    #[clap(
+        long = "keys-only",
+        conflicts_with_all = ["table", "tree", "output", "path_output", "collapse_indices", "files_with_matches", "count_distinct", "key_by", "replace", "delete"],
+        help = "Print only the field name of each match, i.e. the last segment of its path. Each field name is printed once."
+    )]
+    pub keys_only: bool,
+
+    #[clap(
+        long = "max-results",
+        value_name = "N",
+        help = "Abort with an error once more than N results are held in memory, e.g. for --table, --tree, --key-by, --output or --also. Streamed results are not limited."
+    )]
+    pub max_results: Option<usize>,
+
+    #[clap(
+        long = "indices-only-path",
+        conflicts_with_all = ["output", "tree", "collapse_indices", "keys_only", "annotate_types"],
+        help = "Print only the array indices of each path, e.g. '[2][5][3]' for 'matrix.2.5.3', or '[]' if it has none. Intended for matrix-like nested arrays."
+    )]
+    pub indices_only_path: bool,
+
+    #[clap(
+        long = "since",
+        value_name = "DATE",
+        conflicts_with_all = ["replace", "delete"],
+        help = "Skip JSON files last modified before DATE, e.g. '2024-05-01' (local midnight) or '2024-05-01T12:00:00Z'."
+    )]
+    pub since: Option<String>,
+
+    #[clap(
+        long = "until",
+        value_name = "DATE",
+        conflicts_with_all = ["replace", "delete"],
+        help = "Skip JSON files last modified after DATE, e.g. '2024-05-01' (local midnight) or '2024-05-01T12:00:00Z'."
+    )]
+    pub until: Option<String>,
+
+    #[clap(
+        long = "highlight-path",
+        conflicts_with_all = ["table", "tree", "output", "collapse_indices", "keys_only", "indices_only_path", "annotate_types", "path_output"],
+        help = "Highlight the part of each printed path that matched --path-regex or --path-filter, e.g. 'users.12' in 'users.12.name' for 'users\\.\\d+'."
+    )]
+    pub highlight_path: bool,
+
+    #[clap(
+        long = "color",
+        value_name = "WHEN",
+        value_enum,
+        default_value_t = ColorChoice::Auto,
+        help = "When to color the output of --highlight-path. 'auto' colors only output to a terminal."
+    )]
+    pub color: ColorChoice,
+
+    #[clap(
+        long = "duplicates",
+        conflicts_with_all = ["count_distinct", "uniq", "files_with_matches", "keys_only", "collapse_indices", "single", "replace", "delete"],
+        help = "Only print the matches whose value occurs more than once across all inputs, e.g. duplicate emails. Values are compared by their JSON text."
+    )]
+    pub duplicates: bool,
+
+    #[clap(
+        long = "fields",
+        value_name = "FIELDS",
+        value_enum,
+        value_delimiter = ',',
+        help = "Columns of the 'json', 'yaml' and 'sql' output formats, in order, e.g. 'value,path'. Possible fields are 'file', 'path', 'value' and 'matched_on'."
+    )]
+    pub fields: Option<Vec<OutputField>>,
+
+    #[clap(
+        long = "int-range",
+        value_name = "RANGE",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list", "script", "has_non_ascii", "match_array_elements", "jq_value", "contains", "count_matches", "captures"],
+        help = "Match integers within the Rust-style RANGE, '1..100' excluding and '1..=100' including the end. Either bound may be omitted, as in '10..'. SEARCH_TERM is omitted. Floats such as 50.5 or 50.0 never match."
+    )]
+    pub int_range: Option<String>,
+
+    #[clap(
+        long = "equals",
+        value_name = "JSON",
+        conflicts_with_all = ["numeric_search", "exists", "eq", "glob", "concat_search", "aggregate", "compare", "flatten", "semver", "object_size", "divisible_by", "odd", "even", "value_list", "script", "has_non_ascii", "match_array_elements", "jq_value", "contains", "int_range", "count_matches", "captures"],
+        help = "Match values deep-equal to the JSON value, including objects and arrays, e.g. '{\"a\":1}'. Object keys may be in any order. SEARCH_TERM is omitted."
+    )]
+    pub equals: Option<String>,
+
+    #[clap(
+        long = "require-files",
+        help = "Exit with an error if none of the JSON_FILES could be read, e.g. because every path is mistyped, instead of printing no matches."
+    )]
+    pub require_files: bool,
+
+    #[clap(
+        long = "line-range",
+        value_name = "START:END",
+        conflicts_with_all = ["replace", "delete"],
+        help = "Only read the lines START to END of each JSON file, counted from 1 and including END, e.g. '120:180'. Lines past the end of a file are ignored."
+    )]
+    pub line_range: Option<String>,
+
+    #[clap(
+        long = "shell-quote",
+        conflicts_with_all = ["output", "hide_value", "typed_values", "infer_types"],
+        help = "Print each value quoted as a single POSIX shell argument, e.g. 'it'\\''s' for the string \"it's\". Strings are quoted by their content, other values by their JSON text."
+    )]
+    pub shell_quote: bool,
+
+    #[clap(
+        long = "no-recurse-matched",
+        help = "Do not search inside matched values, e.g. a matched object's fields are not reported on their own."
+    )]
+    pub no_recurse_matched: bool,
+
+    #[clap(
+        long = "file-header",
+        conflicts_with_all = ["output", "table", "tree", "key_by", "duplicates", "count_distinct", "files_with_matches"],
+        help = "Print a header like '--- data.json ---' before the matches of each input. Inputs without matches get no header unless --show-empty is given."
+    )]
+    pub file_header: bool,
+
+    #[clap(
+        long = "show-empty",
+        requires = "file_header",
+        conflicts_with = "first_file",
+        help = "With --file-header, also print the header of inputs without matches."
+    )]
+    pub show_empty: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use crate::cli::Cli;
+    use crate::csv_input::InputFormat;
+    use crate::error::ErrorFormat;
+    use crate::format::{ColorChoice, OutputField, OutputFormat};
+    use crate::parse::ArrayMode;
+    use crate::syntax::{JsonType, NormalizationForm, NumberKind};
     use clap::Parser;
 
     #[test]
     fn test_default_values() {
-        let args = Cli::parse_from(&["srch", "field.name", "search"]);
+        let args = Cli::parse_from(["srch", "field.name", "search"]);
         assert_eq!(args.json_string, None);
-        assert_eq!(args.search_path, "field.name");
-        assert_eq!(args.search_term, "search");
+        assert_eq!(args.search_path, Some("field.name".to_string()));
+        assert_eq!(args.search_term, Some("search".to_string()));
         assert_eq!(args.json_files, Vec::<String>::new());
-        assert_eq!(args.single, false);
-        assert_eq!(args.path_output, false);
+        assert!(!args.single);
+        assert!(!args.path_output);
         assert_eq!(args.field_path_separator, ".");
-        assert_eq!(args.hide_value, false);
-        assert_eq!(args.numeric_search, false);
+        assert!(!args.hide_value);
+        assert!(!args.numeric_search);
+        assert_eq!(args.root, None);
+        assert!(!args.coerce_strings);
+        assert_eq!(args.exclude_paths, Vec::<String>::new());
+        assert!(!args.table);
+        assert_eq!(args.max_value_width, 50);
+        assert_eq!(args.replace, None);
+        assert!(!args.delete);
+        assert_eq!(args.all_numeric_terms, Vec::<String>::new());
+        assert_eq!(args.error_format, ErrorFormat::Text);
+        assert!(!args.keys_and_values);
+        assert_eq!(args.max_array_depth, None);
+        assert_eq!(args.max_object_depth, None);
+        assert!(!args.annotate_types);
+        assert_eq!(args.timeout, None);
+        assert_eq!(args.number_kind, None);
+        assert!(!args.exists);
+        assert!(!args.line_numbers);
+        assert!(!args.line_numbers_per_file);
+        assert!(!args.captures);
+        assert!(!args.glob);
+        assert!(!args.parse_embedded);
+        assert_eq!(args.head, None);
+        assert_eq!(args.tail, None);
+        assert_eq!(args.output, OutputFormat::Text);
+        assert_eq!(args.normalize, None);
+        assert_eq!(args.rel_epsilon, 0.0);
+        assert!(!args.collapse_indices);
+        assert!(!args.include_stdin);
+        assert!(!args.concat_search);
+        assert!(!args.eq);
+        assert_eq!(args.max_file_size, None);
+        assert_eq!(args.array_mode, ArrayMode::All);
+        assert!(!args.typed_values);
+        assert!(!args.tree);
+        assert_eq!(args.aggregate, None);
+        assert!(!args.files_with_matches);
+        assert!(!args.first_file);
+        assert_eq!(args.not_types, Vec::<JsonType>::new());
+        assert_eq!(args.path_filter, None);
+        assert_eq!(args.path_regex, None);
+        assert!(!args.siblings);
+        assert!(!args.sibling_values);
+        assert_eq!(args.compare, None);
+        assert!(!args.flatten);
+        assert!(!args.semver);
+        assert!(!args.epoch);
+        assert!(!args.epoch_ms);
+        assert!(!args.count_distinct);
+        assert_eq!(args.object_size, None);
+        assert!(!args.uniq);
+        assert!(!args.count_runs);
+        assert!(!args.explain);
+        assert_eq!(args.read_timeout, None);
+        assert_eq!(args.key_by, None);
+        assert!(!args.base64_decode);
+        assert!(!args.show_decoded);
+        assert_eq!(args.divisible_by, None);
+        assert!(!args.odd);
+        assert!(!args.even);
+        assert!(!args.strip_prefix);
+        assert!(!args.count_matches);
+        assert_eq!(args.field, None);
+        assert_eq!(args.in_path, None);
+        assert!(!args.infer_types);
+        assert!(!args.field_ignore_case);
+        assert_eq!(args.sql_table, "matches");
+        assert_eq!(args.value_list, None);
+        assert!(!args.invert_match);
+        assert!(!args.show_len);
+        assert_eq!(args.sample, None);
+        assert_eq!(args.seed, None);
+        assert!(!args.raw);
+        assert_eq!(args.script, None);
+        assert!(!args.has_non_ascii);
+        assert!(!args.match_array_elements);
+        assert!(!args.all_elements);
+        assert!(!args.no_config);
+        assert!(!args.watch);
+        assert!(!args.watch_append);
+        assert!(!args.parse_number);
+        assert!(!args.canonical_numbers);
+        assert!(!args.coerce_numeric_strings);
+        assert!(!args.fail_on_match);
+        assert!(!args.quiet);
+        assert_eq!(args.input, InputFormat::Json);
+        assert_eq!(args.json_column, None);
+        assert_eq!(args.max_per_file, None);
+        assert_eq!(args.also, None);
+        assert_eq!(args.jq_value, None);
+        assert_eq!(args.contains, None);
+        assert!(!args.keys_only);
+        assert_eq!(args.max_results, None);
+        assert!(!args.indices_only_path);
+        assert_eq!(args.since, None);
+        assert_eq!(args.until, None);
+        assert!(!args.highlight_path);
+        assert_eq!(args.color, ColorChoice::Auto);
+        assert!(!args.duplicates);
+        assert_eq!(args.fields, None);
+        assert_eq!(args.int_range, None);
+        assert_eq!(args.equals, None);
+        assert!(!args.require_files);
+        assert_eq!(args.line_range, None);
+        assert!(!args.shell_quote);
+        assert!(!args.no_recurse_matched);
+        assert!(!args.file_header);
+        assert!(!args.fixed_strings);
+        assert!(!args.whole_value);
+        assert!(!args.fuzzy);
+        assert_eq!(args.max_distance, 2);
+        assert_eq!(args.sort, None);
+        assert!(!args.sort_global);
+        assert!(!args.context);
+        assert_eq!(args.context_depth, 1);
+        assert!(!args.show_empty);
     }
 
     #[test]
     fn test_short_arguments() {
-        let args = Cli::parse_from(&[
+        let args = Cli::parse_from([
             "srch",
             "-j",
             "{\"key\": \"value\"}",
@@ -114,19 +1117,19 @@ mod tests {
             "-n",
         ]);
         assert_eq!(args.json_string, Some("{\"key\": \"value\"}".to_string()));
-        assert_eq!(args.search_path, "field.name");
-        assert_eq!(args.search_term, "search");
+        assert_eq!(args.search_path, Some("field.name".to_string()));
+        assert_eq!(args.search_term, Some("search".to_string()));
         assert_eq!(args.json_files, Vec::<String>::new());
-        assert_eq!(args.single, true);
-        assert_eq!(args.path_output, true);
+        assert!(args.single);
+        assert!(args.path_output);
         assert_eq!(args.field_path_separator, "_");
-        assert_eq!(args.hide_value, true);
-        assert_eq!(args.numeric_search, true);
+        assert!(args.hide_value);
+        assert!(args.numeric_search);
     }
 
     #[test]
     fn test_long_arguments() {
-        let args = Cli::parse_from(&[
+        let args = Cli::parse_from([
             "srch",
             "--json-string",
             "{\"key\": \"value\"}",
@@ -138,21 +1141,355 @@ mod tests {
             "_",
             "--hide-value",
             "--numeric",
+            "--root",
+            "a_b",
+            "--coerce-strings",
+            "--exclude-path",
+            "a_b",
+            "--exclude-path",
+            "c",
+            "--table",
+            "--max-value-width",
+            "20",
+            "--delete",
+            "--all",
+            "<65",
+            "--all",
+            "!=30",
+            "--error-format",
+            "json",
+            "--keys-and-values",
+            "--max-array-depth",
+            "1",
+            "--max-object-depth",
+            "2",
+            "--annotate-types",
+            "--timeout",
+            "0.5",
+            "--number-kind",
+            "int",
         ]);
         assert_eq!(args.json_string, Some("{\"key\": \"value\"}".to_string()));
-        assert_eq!(args.search_path, "field.name");
-        assert_eq!(args.search_term, "search");
+        assert_eq!(args.search_path, Some("field.name".to_string()));
+        assert_eq!(args.search_term, Some("search".to_string()));
         assert_eq!(args.json_files, Vec::<String>::new());
-        assert_eq!(args.single, true);
-        assert_eq!(args.path_output, true);
+        assert!(args.single);
+        assert!(args.path_output);
         assert_eq!(args.field_path_separator, "_");
-        assert_eq!(args.hide_value, true);
-        assert_eq!(args.numeric_search, true);
+        assert!(args.hide_value);
+        assert!(args.numeric_search);
+        assert_eq!(args.root, Some("a_b".to_string()));
+        assert!(args.coerce_strings);
+        assert_eq!(args.exclude_paths, vec!["a_b".to_string(), "c".to_string()]);
+        assert!(args.table);
+        assert_eq!(args.max_value_width, 20);
+        assert!(args.delete);
+        assert_eq!(
+            args.all_numeric_terms,
+            vec!["<65".to_string(), "!=30".to_string()]
+        );
+        assert_eq!(args.error_format, ErrorFormat::Json);
+        assert!(args.keys_and_values);
+        assert_eq!(args.max_array_depth, Some(1));
+        assert_eq!(args.max_object_depth, Some(2));
+        assert!(args.annotate_types);
+        assert_eq!(args.timeout, Some(0.5));
+        assert_eq!(args.number_kind, Some(NumberKind::Int));
+    }
+
+    #[test]
+    fn test_replace_conflicts_with_numeric() {
+        let args = Cli::parse_from(["srch", "field.name", "search", "--replace", "new"]);
+        assert_eq!(args.replace, Some("new".to_string()));
+        assert!(Cli::try_parse_from(["srch", "field.name", ">1", "--replace", "2", "-n"]).is_err());
+    }
+
+    #[test]
+    fn test_search_modes_are_exclusive() {
+        let modes: [&[&str]; 4] = [
+            &["--exists"],
+            &["--value-list", "names.txt"],
+            &["--eq"],
+            &["--glob"],
+        ];
+        for (index, mode) in modes.iter().enumerate() {
+            for other_mode in &modes[index + 1..] {
+                let args = ["srch", "user.name", "Max"]
+                    .iter()
+                    .chain(*mode)
+                    .chain(*other_mode);
+                assert!(Cli::try_parse_from(args).is_err());
+            }
+        }
+        assert!(Cli::try_parse_from(["srch", "user.name", "M*", "--glob"]).is_ok());
+        assert!(Cli::try_parse_from(["srch", "age", ">1", "-n", "--fuzzy"]).is_err());
+    }
+
+    #[test]
+    fn test_replace_conflicts_with_modes_without_regex() {
+        // An empty regex would insert the replacement between all characters
+        for mode in ["--eq", "--fuzzy", "--exists"] {
+            assert!(
+                Cli::try_parse_from(["srch", "user.name", "Max", mode, "--replace", "X"]).is_err()
+            );
+        }
+        assert!(Cli::try_parse_from([
+            "srch",
+            "user.name",
+            "--value-list",
+            "names.txt",
+            "--replace",
+            "X"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_exists_without_search_term() {
+        let args = Cli::parse_from(["srch", "--exists", "field.name", "file1.json"]);
+        assert!(args.exists);
+        assert_eq!(args.search_term, Some("file1.json".to_string()));
+        assert!(Cli::try_parse_from(["srch", "field.name"]).is_err());
+        let args = Cli::parse_from(["srch", "--exists", "field.name"]);
+        assert_eq!(args.search_term, None);
+    }
+
+    #[test]
+    fn test_line_numbers_per_file_requires_line_numbers() {
+        assert!(Cli::try_parse_from(["srch", "--line-numbers-per-file", "a", "b"]).is_err());
+        let args = Cli::parse_from([
+            "srch",
+            "--line-numbers",
+            "--line-numbers-per-file",
+            "a",
+            "b",
+        ]);
+        assert!(args.line_numbers);
+        assert!(args.line_numbers_per_file);
+    }
+
+    #[test]
+    fn test_glob_short_argument() {
+        let args = Cli::parse_from(["srch", "-g", "field.name", "user_*"]);
+        assert!(args.glob);
+        assert!(Cli::try_parse_from(["srch", "-g", "-n", "field.name", ">1"]).is_err());
+    }
+
+    #[test]
+    fn test_head_conflicts_with_tail() {
+        let args = Cli::parse_from(["srch", "--head", "3", "a", "b"]);
+        assert_eq!(args.head, Some(3));
+        let args = Cli::parse_from(["srch", "--tail", "2", "a", "b"]);
+        assert_eq!(args.tail, Some(2));
+        assert!(Cli::try_parse_from(["srch", "--head", "3", "--tail", "2", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_output_format() {
+        let args = Cli::parse_from(["srch", "--output", "yaml", "a", "b"]);
+        assert_eq!(args.output, OutputFormat::Yaml);
+        let args = Cli::parse_from(["srch", "--output", "json", "a", "b"]);
+        assert_eq!(args.output, OutputFormat::Json);
+        assert!(Cli::try_parse_from(["srch", "--output", "json", "--table", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let args = Cli::parse_from(["srch", "--normalize", "nfd", "a", "b"]);
+        assert_eq!(args.normalize, Some(NormalizationForm::Nfd));
+        assert!(Cli::try_parse_from(["srch", "--normalize", "nfkc", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_rel_epsilon() {
+        let args = Cli::parse_from(["srch", "-n", "--rel-epsilon", "0.001", "a", "==5"]);
+        assert_eq!(args.rel_epsilon, 0.001);
+    }
+
+    #[test]
+    fn test_array_mode() {
+        let args = Cli::parse_from(["srch", "--array-mode", "last", "a", "b"]);
+        assert_eq!(args.array_mode, ArrayMode::Last);
+        let args = Cli::parse_from(["srch", "--array-mode", "first", "a", "b"]);
+        assert_eq!(args.array_mode, ArrayMode::First);
+    }
+
+    #[test]
+    fn test_aggregate_arguments() {
+        let args = Cli::parse_from(["srch", "--aggregate", "sum", "a,b", "orders", ">100"]);
+        assert_eq!(
+            args.aggregate,
+            Some(vec!["sum".to_string(), "a,b".to_string()])
+        );
+        assert_eq!(args.search_path, Some("orders".to_string()));
+        assert_eq!(args.search_term, Some(">100".to_string()));
+        assert!(Cli::try_parse_from(["srch", "--aggregate", "sum"]).is_err());
+    }
+
+    #[test]
+    fn test_not_types() {
+        let args = Cli::parse_from([
+            "srch",
+            "--not-type",
+            "string",
+            "--not-type",
+            "null",
+            "--exists",
+            "a",
+        ]);
+        assert_eq!(args.not_types, vec![JsonType::String, JsonType::Null]);
+    }
+
+    #[test]
+    fn test_compare_without_search_term() {
+        let args = Cli::parse_from(["srch", "--compare", "end", "<", "start", "events", "a.json"]);
+        assert_eq!(
+            args.compare,
+            Some(vec![
+                "end".to_string(),
+                "<".to_string(),
+                "start".to_string()
+            ])
+        );
+        assert_eq!(args.search_path, Some("events".to_string()));
+        assert_eq!(args.search_term, Some("a.json".to_string()));
+    }
+
+    #[test]
+    fn test_flatten_without_search_path() {
+        let args = Cli::parse_from(["srch", "--flatten", "a.json", "b.json"]);
+        assert!(args.flatten);
+        assert_eq!(args.search_path, Some("a.json".to_string()));
+        assert_eq!(args.search_term, Some("b.json".to_string()));
+        let args = Cli::parse_from(["srch", "--flatten"]);
+        assert_eq!(args.search_path, None);
+        assert!(Cli::try_parse_from(["srch"]).is_err());
+    }
+
+    #[test]
+    fn test_fixed_strings_conflicts_with_glob() {
+        let args = Cli::parse_from(["srch", "-F", "version", "1.5"]);
+        assert!(args.fixed_strings);
+        let result = Cli::try_parse_from(["srch", "version", "1.*", "-F", "--glob"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_semver_conflicts_with_numeric() {
+        let result = Cli::try_parse_from(["srch", "version", ">=1.2.0", "--semver", "-n"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_object_size_without_search_term() {
+        let args = Cli::parse_from(["srch", "--object-size", ">5", "config", "a.json"]);
+        assert_eq!(args.object_size, Some(">5".to_string()));
+        assert_eq!(args.search_path, Some("config".to_string()));
+        assert_eq!(args.search_term, Some("a.json".to_string()));
+    }
+
+    #[test]
+    fn test_fields() {
+        let args = Cli::parse_from([
+            "srch",
+            "field",
+            "term",
+            "--output",
+            "json",
+            "--fields",
+            "value,path",
+        ]);
+        assert_eq!(
+            args.fields,
+            Some(vec![OutputField::Value, OutputField::Path])
+        );
+
+        let args = Cli::try_parse_from(["srch", "field", "term", "--fields", "path,size"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_count_runs_requires_uniq() {
+        let result = Cli::try_parse_from(["srch", "field", "term", "--count-runs"]);
+        assert!(result.is_err());
+        let args = Cli::parse_from(["srch", "field", "term", "--uniq", "--count-runs"]);
+        assert!(args.count_runs);
+    }
+
+    #[test]
+    fn test_divisible_by() {
+        let args = Cli::parse_from(["srch", "--divisible-by", "3", "qty", "a.json"]);
+        assert_eq!(args.divisible_by, Some(3));
+        assert_eq!(args.search_term, Some("a.json".to_string()));
+        assert!(Cli::try_parse_from(["srch", "--divisible-by", "0", "qty"]).is_err());
+        assert!(Cli::try_parse_from(["srch", "--odd", "--even", "qty"]).is_err());
+    }
+
+    #[test]
+    fn test_field_without_search_path() {
+        let args = Cli::parse_from(["srch", "--in-path", "a.b", "--field", "c", "term", "a.json"]);
+        assert_eq!(args.in_path, Some("a.b".to_string()));
+        assert_eq!(args.field, Some("c".to_string()));
+        assert_eq!(args.search_path, Some("term".to_string()));
+        assert_eq!(args.search_term, Some("a.json".to_string()));
+        assert!(Cli::try_parse_from(["srch", "--in-path", "a.b", "term"]).is_err());
+    }
+
+    #[test]
+    fn test_value_list_without_search_term() {
+        let args = Cli::parse_from(["srch", "--value-list", "ids.txt", "-v", "id", "a.json"]);
+        assert_eq!(args.value_list, Some("ids.txt".to_string()));
+        assert!(args.invert_match);
+        assert_eq!(args.search_term, Some("a.json".to_string()));
+    }
+
+    #[test]
+    fn test_script_without_search_term() {
+        let args = Cli::parse_from(["srch", "--script", "Cyrillic", "name", "a.json"]);
+        assert_eq!(args.script, Some("Cyrillic".to_string()));
+        assert_eq!(args.search_term, Some("a.json".to_string()));
+        assert!(Cli::try_parse_from(["srch", "--script", "Han", "--has-non-ascii", "a"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_input_requires_json_column() {
+        let args = Cli::parse_from([
+            "srch",
+            "--input",
+            "csv",
+            "--json-column",
+            "payload",
+            "a",
+            "b",
+        ]);
+        assert_eq!(args.input, InputFormat::Csv);
+        assert_eq!(args.json_column, Some("payload".to_string()));
+        assert!(Cli::try_parse_from(["srch", "--input", "csv", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_also_format_and_path() {
+        let args = Cli::parse_from(["srch", "--also", "json", "out.json", "a", "b", "c.json"]);
+        assert_eq!(
+            args.also,
+            Some(vec!["json".to_string(), "out.json".to_string()])
+        );
+        assert_eq!(args.json_files, vec!["c.json".to_string()]);
+        assert!(Cli::try_parse_from(["srch", "a", "b", "--also", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_sample_fraction() {
+        let args = Cli::parse_from(["srch", "a", "b", "--sample", "0.25", "--seed", "42"]);
+        assert_eq!(args.sample, Some(0.25));
+        assert_eq!(args.seed, Some(42));
+        assert!(Cli::try_parse_from(["srch", "a", "b", "--sample", "1.5"]).is_err());
+        assert!(Cli::try_parse_from(["srch", "a", "b", "--seed", "42"]).is_err());
     }
 
     #[test]
     fn test_json_files_argument() {
-        let args = Cli::parse_from(&["srch", "field.name", "search", "file1.json", "file2.json"]);
+        let args = Cli::parse_from(["srch", "field.name", "search", "file1.json", "file2.json"]);
         assert_eq!(
             args.json_files,
             vec!["file1.json".to_string(), "file2.json".to_string()]
@@ -0,0 +1,148 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+use directories::BaseDirs;
+
+use crate::cli::Cli;
+use crate::error::{ErrorKind, SrchError};
+
+/// Returns `./.srch.toml` if it exists, else the user config, e.g. `~/.config/srch/config.toml`.
+fn config_path() -> Option<PathBuf> {
+    let local_config = PathBuf::from(".srch.toml");
+    if local_config.is_file() {
+        return Some(local_config);
+    }
+    let user_config = BaseDirs::new()?
+        .config_dir()
+        .join("srch")
+        .join("config.toml");
+    user_config.is_file().then_some(user_config)
+}
+
+/// Parses the command line, filling the options it leaves unset from the config file.
+/// Precedence: command line and environment variables, then config file, then built-in default.
+pub fn parse_with_config() -> Cli {
+    let args: Vec<OsString> = std::env::args_os().collect();
+    let matches = Cli::command().get_matches_from(&args);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if cli.no_config {
+        return cli;
+    }
+    let Some(config_path) = config_path() else {
+        return cli;
+    };
+    let config_file = config_path.to_string_lossy();
+    fs::read_to_string(&config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|config| apply_config(&args, &matches, &config))
+        .unwrap_or_else(|message| {
+            SrchError::new(ErrorKind::Config, message, Some(&config_file)).exit(cli.error_format)
+        })
+}
+
+/// Parses the arguments again with the config options inserted before them. Config keys are
+/// long option names, e.g. `field-path-separator = "/"` or `single = true`.
+fn apply_config(args: &[OsString], matches: &ArgMatches, config: &str) -> Result<Cli, String> {
+    let config: toml::Table = config.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let command = Cli::command();
+    let mut config_args = Vec::new();
+    for (key, value) in &config {
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(key.as_str()))
+            .ok_or_else(|| format!("unknown option '{}'", key))?;
+        if matches!(
+            matches.value_source(arg.get_id().as_str()),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+        let takes_values = arg.get_action().takes_values();
+        let values = match value {
+            toml::Value::Array(values) => values.iter().collect(),
+            _ => vec![value],
+        };
+        for value in values {
+            let value_string = match value {
+                toml::Value::Boolean(flag) if !takes_values => {
+                    if *flag {
+                        config_args.push(OsString::from(format!("--{}", key)));
+                    }
+                    continue;
+                }
+                toml::Value::String(string_value) => string_value.clone(),
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                    value.to_string()
+                }
+                _ => return Err(format!("unsupported value for option '{}'", key)),
+            };
+            config_args.push(OsString::from(format!("--{}={}", key, value_string)));
+        }
+    }
+    let combined_args = args
+        .iter()
+        .take(1)
+        .chain(&config_args)
+        .chain(args.iter().skip(1));
+    Cli::try_parse_from(combined_args).map_err(|e| e.to_string().trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::OutputFormat;
+
+    fn parse_with(args: &[&str], config: &str) -> Result<Cli, String> {
+        let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        let matches = Cli::command().get_matches_from(&args);
+        apply_config(&args, &matches, config)
+    }
+
+    #[test]
+    fn test_config_sets_defaults() {
+        let config = r#"
+            output = "json"
+            single = true
+            exclude-path = ["a.secret", "b"]
+        "#;
+        let args = parse_with(&["srch", "name", "term", "a.json"], config).unwrap();
+        assert_eq!(args.output, OutputFormat::Json);
+        assert!(args.single);
+        assert_eq!(args.exclude_paths, vec!["a.secret", "b"]);
+        assert_eq!(args.search_path, Some("name".to_string()));
+        assert_eq!(args.json_files, vec!["a.json"]);
+    }
+
+    #[test]
+    fn test_readme_config_example() {
+        let readme = include_str!("../README.md");
+        let config = readme
+            .split("## Configuration File")
+            .nth(1)
+            .and_then(|section| section.split("```toml\n").nth(1))
+            .and_then(|block| block.split("```").next())
+            .unwrap();
+        let args = parse_with(&["srch", "name", "term"], config).unwrap();
+        assert_eq!(args.field_path_separator, "/");
+        assert_eq!(args.output, OutputFormat::Json);
+        assert_eq!(args.exclude_paths, vec!["metadata", "secrets.token"]);
+    }
+
+    #[test]
+    fn test_command_line_overrides_config() {
+        let config = "output = \"json\"\nsingle = false";
+        let args = parse_with(&["srch", "--output", "yaml", "-s", "name", "term"], config).unwrap();
+        assert_eq!(args.output, OutputFormat::Yaml);
+        assert!(args.single);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(parse_with(&["srch", "name", "term"], "unknown-option = 1").is_err());
+        assert!(parse_with(&["srch", "name", "term"], "output = [").is_err());
+        assert!(parse_with(&["srch", "name", "term"], "output = \"csv\"").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use clap::ValueEnum;
+
+use crate::parse::{process_json_input_with, SearchContext, SearchResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum InputFormat {
+    Json,
+    Csv,
+}
+
+/// Searches the JSON in the column of each CSV row. Result paths start with the row number,
+/// counting the rows after the header from 1. Cells that are not valid JSON are passed to
+/// `on_row_error` and skipped. Fails if the CSV is malformed or lacks the column.
+pub fn process_csv_input_with(
+    csv_input: &str,
+    json_column: &str,
+    field_path_parts: &[&str],
+    field_name: &str,
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+    on_row_error: &mut dyn FnMut(usize, serde_json::Error),
+) -> Result<(), String> {
+    let mut reader = csv::Reader::from_reader(csv_input.as_bytes());
+    let column_index = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .position(|header| header == json_column)
+        .ok_or_else(|| format!("no column named '{}'", json_column))?;
+    let mut has_matches = false;
+    for (row_index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| e.to_string())?;
+        let row_number = row_index + 1;
+        let Some(json_cell) = record.get(column_index) else {
+            continue;
+        };
+        let processed = process_json_input_with(
+            json_cell.to_string(),
+            field_path_parts,
+            field_name,
+            search_context,
+            &mut |mut search_result| {
+                search_result.json_path.insert(0, row_number.to_string());
+                has_matches = true;
+                on_result(search_result);
+            },
+        );
+        if let Err(e) = processed {
+            on_row_error(row_number, e);
+        }
+        // One match per input is enough, as for JSON input
+        if (has_matches && search_context.single_result_only) || search_context.deadline_exceeded()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    const CSV_INPUT: &str = r#"id,payload
+1,"{""user"": {""name"": ""Ann"", ""address"": {""city"": ""Berlin""}}}"
+2,"{""user"": {""name"": ""Bob"", ""address"": {""city"": ""Paris""}}}"
+3,not json
+4,"{""user"": {""name"": ""Cy"", ""address"": {""city"": ""Bern""}}}"
+"#;
+
+    fn search_csv(
+        json_column: &str,
+        search_context: &SearchContext,
+    ) -> Result<(Vec<String>, Vec<usize>), String> {
+        let mut found_paths = Vec::new();
+        let mut error_rows = Vec::new();
+        process_csv_input_with(
+            CSV_INPUT,
+            json_column,
+            &["user", "address"],
+            "city",
+            search_context,
+            &mut |result| found_paths.push(result.json_path.join(".")),
+            &mut |row_number, _| error_rows.push(row_number),
+        )?;
+        Ok((found_paths, error_rows))
+    }
+
+    #[test]
+    fn test_process_csv_input_nested_json() {
+        let search_regex = Regex::new("Ber").unwrap();
+        let search_context = SearchContext::with_defaults(&search_regex);
+        let (found_paths, error_rows) = search_csv("payload", &search_context).unwrap();
+        assert_eq!(
+            found_paths,
+            vec!["1.user.address.city", "4.user.address.city"]
+        );
+        assert_eq!(error_rows, vec![3]);
+
+        let search_context = SearchContext {
+            single_result_only: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let (found_paths, _) = search_csv("payload", &search_context).unwrap();
+        assert_eq!(found_paths, vec!["1.user.address.city"]);
+    }
+
+    #[test]
+    fn test_process_csv_input_missing_column() {
+        let search_regex = Regex::new("").unwrap();
+        let search_context = SearchContext::with_defaults(&search_regex);
+        assert_eq!(
+            search_csv("json", &search_context),
+            Err("no column named 'json'".to_string())
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Set once an error is reported, so that a search continuing with the other inputs still exits
+/// with a nonzero code.
+static ERROR_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether an error was reported, e.g. for an unreadable file among several inputs. Files
+/// skipped by --max-file-size are not errors.
+pub fn error_reported() -> bool {
+    ERROR_REPORTED.load(Ordering::Relaxed)
+}
+
+/// Exit code after an error, distinct from the code 1 of --fail-on-match like in grep.
+pub const ERROR_EXIT_CODE: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ErrorFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    JsonParse,
+    FileRead,
+    StdinRead,
+    Regex,
+    SearchPath,
+    NumericTerm,
+    Timeout,
+    FileTooLarge,
+    Aggregate,
+    Compare,
+    VersionTerm,
+    Config,
+    Watch,
+    CsvParse,
+    FileWrite,
+    ValueFilter,
+    TooManyResults,
+    Date,
+    IntRange,
+    NoReadableInput,
+    LineRange,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SrchError {
+    #[serde(rename = "error")]
+    pub message: String,
+    pub kind: ErrorKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+impl SrchError {
+    pub fn new(kind: ErrorKind, message: impl ToString, file: Option<&str>) -> Self {
+        SrchError {
+            message: message.to_string(),
+            kind,
+            file: file.map(|file_path| file_path.to_string()),
+        }
+    }
+
+    pub fn render(&self, error_format: ErrorFormat) -> String {
+        match error_format {
+            ErrorFormat::Text => match self.kind {
+                ErrorKind::JsonParse => format!("JSON parsing error: {}", self.message),
+                ErrorKind::FileRead => format!(
+                    "Error reading file '{}': {}",
+                    self.file.as_deref().unwrap_or_default(),
+                    self.message
+                ),
+                ErrorKind::StdinRead => format!("Error reading from stdin: {}", self.message),
+                ErrorKind::Regex => format!("Error parsing search term as regex: {}", self.message),
+                ErrorKind::SearchPath => format!("Error parsing search path: {}", self.message),
+                ErrorKind::NumericTerm => {
+                    format!("Error parsing numeric search term: {}", self.message)
+                }
+                ErrorKind::Timeout | ErrorKind::TooManyResults | ErrorKind::NoReadableInput => {
+                    format!("Search aborted: {}", self.message)
+                }
+                ErrorKind::Aggregate => format!("Error parsing aggregate: {}", self.message),
+                ErrorKind::Compare => format!("Error parsing comparison: {}", self.message),
+                ErrorKind::VersionTerm => {
+                    format!("Error parsing version search term: {}", self.message)
+                }
+                ErrorKind::Config => format!(
+                    "Error in config file '{}': {}",
+                    self.file.as_deref().unwrap_or_default(),
+                    self.message
+                ),
+                ErrorKind::Watch => format!("Error watching files: {}", self.message),
+                ErrorKind::CsvParse => format!("CSV parsing error: {}", self.message),
+                ErrorKind::FileWrite => format!(
+                    "Error writing file '{}': {}",
+                    self.file.as_deref().unwrap_or_default(),
+                    self.message
+                ),
+                ErrorKind::ValueFilter => format!("Error parsing value filter {}", self.message),
+                ErrorKind::Date => format!("Error parsing date {}", self.message),
+                ErrorKind::IntRange => format!("Error parsing integer range {}", self.message),
+                ErrorKind::LineRange => format!("Error parsing line range {}", self.message),
+                ErrorKind::FileTooLarge => format!(
+                    "Skipping file '{}': {}",
+                    self.file.as_deref().unwrap_or_default(),
+                    self.message
+                ),
+            },
+            ErrorFormat::Json => serde_json::to_string(self).unwrap_or_default(),
+        }
+    }
+
+    pub fn report(&self, error_format: ErrorFormat) {
+        eprintln!("{}", self.render(error_format));
+        if self.kind != ErrorKind::FileTooLarge {
+            ERROR_REPORTED.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Reports the error and terminates with the error exit code.
+    pub fn exit(&self, error_format: ErrorFormat) -> ! {
+        self.report(error_format);
+        std::process::exit(ERROR_EXIT_CODE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_json_parse_error_as_json() {
+        let parse_error = serde_json::from_str::<serde_json::Value>("{invalid json").unwrap_err();
+        let error = SrchError::new(ErrorKind::JsonParse, &parse_error, Some("data.json"));
+        let rendered: serde_json::Value =
+            serde_json::from_str(&error.render(ErrorFormat::Json)).unwrap();
+        assert_eq!(
+            rendered,
+            json!({
+                "error": parse_error.to_string(),
+                "kind": "json_parse",
+                "file": "data.json"
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_json_without_file() {
+        let error = SrchError::new(ErrorKind::Regex, "unclosed group", None);
+        assert_eq!(
+            error.render(ErrorFormat::Json),
+            r#"{"error":"unclosed group","kind":"regex"}"#
+        );
+    }
+
+    #[test]
+    fn test_render_text() {
+        let error = SrchError::new(ErrorKind::FileRead, "No such file", Some("data.json"));
+        assert_eq!(
+            error.render(ErrorFormat::Text),
+            "Error reading file 'data.json': No such file"
+        );
+    }
+}
@@ -1,77 +1,1720 @@
-use std::fs;
-use std::io::{self, BufReader, Read};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
-use crate::format::format_text_output;
-use crate::format::FormatContext;
-use crate::parse::{process_json_input, SearchContext, SearchResult};
+use flate2::read::MultiGzDecoder;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
 
-fn read_from_stdin() -> Result<String, io::Error> {
+use crate::csv_input::process_csv_input_with;
+use crate::error::{ErrorFormat, ErrorKind, SrchError};
+use crate::format::{
+    format_collapsed_path, format_keyed_output, format_structured_output, format_table_output,
+    format_text_output, format_tree_output, infer_type,
+};
+use crate::format::{FormatContext, OutputFormat, SortKey};
+use crate::parse::{process_json_input_with, SearchContext, SearchResult};
+use crate::syntax::LineRange;
+use crate::transform::{process_json_transform, Transform};
+
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
+/// Wraps the input in a decompressor if it starts with the gzip magic bytes.
+fn decompressed_reader<'a>(reader: impl Read + 'a) -> Result<Box<dyn BufRead + 'a>, io::Error> {
+    let mut reader = BufReader::new(reader);
+    let is_gzip = reader.fill_buf()?.starts_with(&GZIP_MAGIC_BYTES);
+    if is_gzip {
+        // Concatenated gzip members, as written by `cat a.gz b.gz`, are read in full
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Reads the whole input, decompressing it if it starts with the gzip magic bytes.
+fn read_to_string_decompressed(reader: impl Read) -> Result<String, io::Error> {
     let mut buffer = String::new();
-    let stdin = io::stdin();
-    let mut reader = BufReader::new(stdin);
-    reader.read_to_string(&mut buffer)?;
+    decompressed_reader(reader)?.read_to_string(&mut buffer)?;
     Ok(buffer)
 }
 
-fn process_file(
+/// Reads only the lines of --line-range, decompressing the input if needed. Reading stops after
+/// the last line of the range, and lines past the end of the input are ignored, so a range
+/// starting after the last line yields an empty input.
+fn read_line_range_decompressed(
+    reader: impl Read,
+    line_range: LineRange,
+) -> Result<String, io::Error> {
+    let lines = decompressed_reader(reader)?
+        .lines()
+        .skip(line_range.start - 1)
+        .take(line_range.end - line_range.start + 1)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// Reads newline-delimited values for --value-list. Empty lines are skipped.
+pub fn read_value_list(file_path: &str) -> Result<HashSet<String>, io::Error> {
+    let content = fs::read_to_string(file_path)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Runs the read on a background thread for --read-timeout, so that a FIFO whose writer never
+/// closes cannot block forever. The abandoned thread ends with the process.
+fn read_with_timeout(
+    read: impl FnOnce() -> Result<String, io::Error> + Send + 'static,
+    read_timeout: Option<Duration>,
+) -> Result<String, io::Error> {
+    let Some(read_timeout) = read_timeout else {
+        return read();
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(read()));
+    receiver.recv_timeout(read_timeout).unwrap_or_else(|_| {
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("no end of input within {} ms", read_timeout.as_millis()),
+        ))
+    })
+}
+
+fn read_from_stdin(read_timeout: Option<Duration>) -> Result<String, io::Error> {
+    read_with_timeout(|| read_to_string_decompressed(io::stdin()), read_timeout)
+}
+
+fn read_from_file(
+    file_path: &str,
+    read_timeout: Option<Duration>,
+    line_range: Option<LineRange>,
+) -> Result<String, io::Error> {
+    let file_path = file_path.to_string();
+    // Opening a FIFO also blocks until a writer opens it
+    read_with_timeout(
+        move || {
+            let file = File::open(file_path)?;
+            match line_range {
+                Some(line_range) => read_line_range_decompressed(file, line_range),
+                None => read_to_string_decompressed(file),
+            }
+        },
+        read_timeout,
+    )
+}
+
+fn read_file_or_report(
     file_path: &str,
+    search_context: &SearchContext,
+    error_format: ErrorFormat,
+) -> Option<String> {
+    if let Some(max_file_size) = search_context.max_file_size {
+        // Metadata errors are reported when opening the file
+        if let Ok(metadata) = fs::metadata(file_path) {
+            if metadata.len() > max_file_size {
+                let message = format!(
+                    "size of {} bytes exceeds the limit of {} bytes",
+                    metadata.len(),
+                    max_file_size
+                );
+                SrchError::new(ErrorKind::FileTooLarge, message, Some(file_path))
+                    .report(error_format);
+                return None;
+            }
+        }
+    }
+    match read_from_file(
+        file_path,
+        search_context.read_timeout,
+        search_context.line_range,
+    ) {
+        Ok(file_content) => Some(file_content),
+        Err(e) => {
+            SrchError::new(ErrorKind::FileRead, e, Some(file_path)).report(error_format);
+            None
+        }
+    }
+}
+
+fn read_string_or_stdin(
+    json_string: &Option<String>,
+    read_timeout: Option<Duration>,
+    error_format: ErrorFormat,
+) -> String {
+    match json_string {
+        Some(json_str) => json_str.clone(),
+        None => match read_from_stdin(read_timeout) {
+            Ok(stdin_json) => stdin_json,
+            Err(e) => SrchError::new(ErrorKind::StdinRead, e, None).exit(error_format),
+        },
+    }
+}
+
+/// Flushes the results printed so far and exits with a timeout error.
+fn exit_on_timeout(error_format: ErrorFormat) -> ! {
+    let _ = io::stdout().flush();
+    SrchError::new(ErrorKind::Timeout, "time limit exceeded", None).exit(error_format)
+}
+
+/// Numbers the printed lines if --line-numbers is given.
+struct LineNumbers {
+    next: Option<usize>,
+}
+
+impl LineNumbers {
+    fn new(format_context: &FormatContext) -> Self {
+        LineNumbers {
+            next: format_context.line_numbers.then_some(1),
+        }
+    }
+
+    fn reset(&mut self) {
+        if let Some(next) = &mut self.next {
+            *next = 1;
+        }
+    }
+
+    fn number(&mut self, output: String) -> String {
+        match &mut self.next {
+            Some(next) => {
+                let numbered = format!("{}: {}", next, output);
+                *next += 1;
+                numbered
+            }
+            None => output,
+        }
+    }
+}
+
+/// Formats a result for the text output. With --collapse-indices, only the first
+/// occurrence of each collapsed path is printed, or of each path and type for --infer-types.
+/// With --keys-only, only the first occurrence of each field name is printed.
+fn format_text_line(
+    result: &SearchResult,
+    file_path: Option<&str>,
+    format_context: &FormatContext,
+    seen_paths: &mut HashSet<String>,
+) -> Option<String> {
+    let unique_line = if format_context.keys_only {
+        // A match on the root value has no field name
+        result.json_path.last()?.clone()
+    } else if format_context.collapse_indices {
+        let collapsed_path = format_collapsed_path(result, format_context);
+        if format_context.infer_types {
+            format!("{}: {}", collapsed_path, infer_type(&result.value))
+        } else {
+            collapsed_path
+        }
+    } else {
+        return Some(format_text_output(result, file_path, format_context));
+    };
+    seen_paths
+        .insert(unique_line.clone())
+        .then_some(unique_line)
+}
+
+/// Keeps each match with the --sample probability as it streams out. The same --seed keeps
+/// the same matches.
+struct Sampler {
+    fraction: Option<f64>,
+    rng: StdRng,
+}
+
+impl Sampler {
+    fn new(format_context: &FormatContext) -> Self {
+        Sampler {
+            fraction: format_context.sample,
+            rng: match format_context.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_os_rng(),
+            },
+        }
+    }
+
+    fn keep(&mut self) -> bool {
+        self.fraction
+            .is_none_or(|fraction| self.rng.random_bool(fraction))
+    }
+}
+
+/// Suppresses printed lines whose value repeats the previous one for --uniq. With --count-runs,
+/// each run is printed once it ends, prefixed with its length.
+struct ValueRuns {
+    enabled: bool,
+    count_runs: bool,
+    current: Option<(String, String, usize)>,
+}
+
+impl ValueRuns {
+    fn new(format_context: &FormatContext) -> Self {
+        ValueRuns {
+            enabled: format_context.uniq,
+            count_runs: format_context.count_runs,
+            current: None,
+        }
+    }
+
+    /// Returns the line to print now, if any.
+    fn push(&mut self, value: &Value, output: String) -> Option<String> {
+        if !self.enabled {
+            return Some(output);
+        }
+        let value_string = value.to_string();
+        match &mut self.current {
+            Some((run_value, _, run_length)) if *run_value == value_string => {
+                *run_length += 1;
+                None
+            }
+            _ => {
+                let finished_run = self.current.replace((value_string, output.clone(), 1));
+                if self.count_runs {
+                    finished_run.map(Self::format_run)
+                } else {
+                    Some(output)
+                }
+            }
+        }
+    }
+
+    /// Returns the last run if it is still to be printed.
+    fn finish(&mut self) -> Option<String> {
+        self.current
+            .take()
+            .filter(|_| self.count_runs)
+            .map(Self::format_run)
+    }
+
+    fn format_run((_, output, run_length): (String, String, usize)) -> String {
+        format!("{} {}", run_length, output)
+    }
+}
+
+/// Headers like `--- data.json ---` before the results of each input for --file-header. Inputs
+/// without matches only get a header with --show-empty, once a later input has matches or the
+/// search ends.
+struct FileHeaders<'a> {
+    enabled: bool,
+    show_empty: bool,
+    labels: Vec<&'a str>,
+    /// Index of the first input whose header is not printed yet.
+    next_input_index: usize,
+}
+
+impl<'a> FileHeaders<'a> {
+    fn new(input_sources: &[InputSource<'a>], format_context: &FormatContext) -> Self {
+        FileHeaders {
+            enabled: format_context.file_header,
+            show_empty: format_context.show_empty,
+            labels: input_sources.iter().map(InputSource::label).collect(),
+            next_input_index: 0,
+        }
+    }
+
+    /// Returns the headers to print before a result of the input.
+    fn before_result(&mut self, input_index: usize) -> Vec<String> {
+        if !self.enabled || input_index < self.next_input_index {
+            return Vec::new();
+        }
+        let first_header = if self.show_empty {
+            self.next_input_index
+        } else {
+            input_index
+        };
+        self.next_input_index = input_index + 1;
+        self.format_headers(first_header..=input_index)
+    }
+
+    /// Returns the headers of the remaining inputs without matches for --show-empty.
+    fn finish(&mut self) -> Vec<String> {
+        if !(self.enabled && self.show_empty) {
+            return Vec::new();
+        }
+        let remaining = self.next_input_index..self.labels.len();
+        self.next_input_index = self.labels.len();
+        self.format_headers(remaining)
+    }
+
+    fn format_headers(&self, input_indices: impl Iterator<Item = usize>) -> Vec<String> {
+        input_indices
+            .map(|input_index| format!("--- {} ---", self.labels[input_index]))
+            .collect()
+    }
+}
+
+/// Collects the distinct matched values for --count-distinct, per input and in total.
+#[derive(Default)]
+struct DistinctValues<'a> {
+    per_input: Vec<(&'a str, HashSet<String>)>,
+    total: HashSet<String>,
+}
+
+impl<'a> DistinctValues<'a> {
+    fn insert(&mut self, is_new_input: bool, label: &'a str, value: &Value) {
+        if is_new_input {
+            self.per_input.push((label, HashSet::new()));
+        }
+        // Values are compared by their JSON text, so 1 and "1" are distinct
+        let value_string = value.to_string();
+        if let Some((_, input_values)) = self.per_input.last_mut() {
+            input_values.insert(value_string.clone());
+        }
+        self.total.insert(value_string);
+    }
+
+    /// One 'label: count' line per input with matches, followed by the total if requested.
+    fn format_counts(&self, with_total: bool) -> Vec<String> {
+        let input_lines = self
+            .per_input
+            .iter()
+            .map(|(label, input_values)| format!("{}: {}", label, input_values.len()));
+        let total_line = with_total.then(|| format!("total: {}", self.total.len()));
+        input_lines.chain(total_line).collect()
+    }
+}
+
+/// Name under which results from stdin are reported alongside files.
+const STDIN_LABEL: &str = "<stdin>";
+
+#[derive(Debug, PartialEq)]
+enum InputSource<'a> {
+    Stdin,
+    File(&'a str),
+}
+
+impl<'a> InputSource<'a> {
+    fn label(&self) -> &'a str {
+        match self {
+            InputSource::Stdin => STDIN_LABEL,
+            InputSource::File(file_path) => file_path,
+        }
+    }
+
+    fn read_or_report(
+        &self,
+        search_context: &SearchContext,
+        error_format: ErrorFormat,
+    ) -> Option<String> {
+        match self {
+            InputSource::Stdin => match read_from_stdin(search_context.read_timeout) {
+                Ok(stdin_json) => Some(stdin_json),
+                Err(e) => {
+                    SrchError::new(ErrorKind::StdinRead, e, None).report(error_format);
+                    None
+                }
+            },
+            InputSource::File(file_path) => {
+                read_file_or_report(file_path, search_context, error_format)
+            }
+        }
+    }
+}
+
+/// Lists the inputs to search. With --include-stdin, piped stdin is searched before the files.
+fn input_sources(
+    json_files: &[String],
+    include_stdin: bool,
+    stdin_is_terminal: bool,
+) -> Vec<InputSource<'_>> {
+    let stdin = (include_stdin && !stdin_is_terminal).then_some(InputSource::Stdin);
+    stdin
+        .into_iter()
+        .chain(
+            json_files
+                .iter()
+                .map(|file_path| InputSource::File(file_path)),
+        )
+        .collect()
+}
+
+/// Whether the file was last modified within --since and --until. Files whose modification
+/// time cannot be read are kept, so that reading them reports the error.
+fn is_modified_in_window(file_path: &str, search_context: &SearchContext) -> bool {
+    if search_context.modified_since.is_none() && search_context.modified_until.is_none() {
+        return true;
+    }
+    let Ok(modified) = fs::metadata(file_path).and_then(|metadata| metadata.modified()) else {
+        return true;
+    };
+    search_context
+        .modified_since
+        .is_none_or(|since| modified >= since)
+        && search_context
+            .modified_until
+            .is_none_or(|until| modified <= until)
+}
+
+/// Searches the input, passing each result to `on_result` as soon as it is found. Files
+/// outside the --since and --until window are skipped before they are read. Returns whether the
+/// input was read or deliberately skipped, as opposed to missing or unreadable.
+fn process_file(
+    input_source: &InputSource,
     field_path_parts: &[&str],
     field_name: &str,
     search_context: &SearchContext,
-) -> Vec<SearchResult> {
-    match fs::read_to_string(file_path) {
-        Ok(file_content) => {
-            process_json_input(file_content, field_path_parts, field_name, search_context)
-                .unwrap_or_default()
+    error_format: ErrorFormat,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    if let InputSource::File(file_path) = input_source {
+        if !is_modified_in_window(file_path, search_context) {
+            return true;
         }
+    }
+    let Some(file_content) = input_source.read_or_report(search_context, error_format) else {
+        return false;
+    };
+    process_input(
+        file_content,
+        Some(input_source.label()),
+        field_path_parts,
+        field_name,
+        search_context,
+        error_format,
+        on_result,
+    );
+    true
+}
+
+/// Searches the input as JSON, or with --input csv as CSV rows, reporting parse errors.
+/// Results beyond --max-per-file are dropped. Returns whether the input could be parsed.
+fn process_input(
+    input: String,
+    label: Option<&str>,
+    field_path_parts: &[&str],
+    field_name: &str,
+    search_context: &SearchContext,
+    error_format: ErrorFormat,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    let mut result_count = 0;
+    let on_result = &mut |search_result| {
+        if search_context
+            .max_per_file
+            .is_none_or(|max_per_file| result_count < max_per_file)
+        {
+            result_count += 1;
+            on_result(search_result);
+        }
+    };
+    let Some(json_column) = search_context.csv_json_column else {
+        return match process_json_input_with(
+            input,
+            field_path_parts,
+            field_name,
+            search_context,
+            on_result,
+        ) {
+            Ok(_) => true,
+            Err(e) => {
+                SrchError::new(ErrorKind::JsonParse, e, label).report(error_format);
+                false
+            }
+        };
+    };
+    let processed = process_csv_input_with(
+        &input,
+        json_column,
+        field_path_parts,
+        field_name,
+        search_context,
+        on_result,
+        &mut |row_number, e| {
+            let message = format!("row {}: {}", row_number, e);
+            SrchError::new(ErrorKind::JsonParse, message, label).report(error_format);
+        },
+    );
+    match processed {
+        Ok(()) => true,
         Err(e) => {
-            eprintln!("Error reading file '{}': {}", file_path, e);
-            Vec::new()
+            SrchError::new(ErrorKind::CsvParse, e, label).report(error_format);
+            false
+        }
+    }
+}
+
+/// Searches the inputs in order and passes each result to `handle_result` with the index and
+/// label of its input. Stops early after the first input with matches for --first-file, or on
+/// timeout. Returns whether any input could be read.
+fn search_inputs<'a>(
+    input_sources: &[InputSource<'a>],
+    field_path_parts: &[&str],
+    field_name: &str,
+    search_context: &SearchContext,
+    format_context: &FormatContext,
+    mut handle_result: impl FnMut(usize, &'a str, SearchResult),
+) -> bool {
+    let mut any_read = false;
+    for (input_index, input_source) in input_sources.iter().enumerate() {
+        let mut has_matches = false;
+        any_read |= process_file(
+            input_source,
+            field_path_parts,
+            field_name,
+            search_context,
+            format_context.error_format,
+            &mut |search_result| {
+                has_matches = true;
+                handle_result(input_index, input_source.label(), search_result);
+            },
+        );
+        if has_matches && format_context.first_file {
+            break;
+        }
+        if search_context.deadline_exceeded() {
+            break; // Print what was found so far
         }
     }
+    any_read
+}
+
+/// Holds a result until all inputs are searched. Fails once more than --max-results results
+/// are held, before a pathological query exhausts the memory.
+fn buffer_result<'a>(
+    buffered_results: &mut Vec<(Option<&'a str>, SearchResult)>,
+    file_path: Option<&'a str>,
+    result: SearchResult,
+    max_results: Option<usize>,
+) -> Result<(), String> {
+    if let Some(max_results) = max_results {
+        if buffered_results.len() >= max_results {
+            return Err(format!(
+                "more than {} results to hold in memory, see --max-results",
+                max_results
+            ));
+        }
+    }
+    buffered_results.push((file_path, result));
+    Ok(())
+}
+
+fn buffer_or_exit<'a>(
+    buffered_results: &mut Vec<(Option<&'a str>, SearchResult)>,
+    file_path: Option<&'a str>,
+    result: SearchResult,
+    format_context: &FormatContext,
+) {
+    buffer_result(
+        buffered_results,
+        file_path,
+        result,
+        format_context.max_results,
+    )
+    .unwrap_or_else(|e| {
+        SrchError::new(ErrorKind::TooManyResults, e, None).exit(format_context.error_format)
+    });
 }
 
+/// Returns whether any match was found. With `require_files`, exits with an error if none of
+/// the inputs could be read, so that mistyped paths are not mistaken for a lack of matches.
 pub fn handle_file_input(
-    json_files: &Vec<String>,
+    json_files: &[String],
+    include_stdin: bool,
+    require_files: bool,
     field_path_parts: &[&str],
     field_name: &str,
     search_context: &SearchContext,
     format_context: &FormatContext,
+) -> bool {
+    // The table and structured formats need all results across files
+    let buffer_results = format_context.buffers_results();
+    let mut buffered_results = Vec::new();
+    let mut line_numbers = LineNumbers::new(format_context);
+    let mut seen_paths = HashSet::new();
+    let mut previous_input_index = None;
+    let mut distinct_values = DistinctValues::default();
+    let mut value_runs = ValueRuns::new(format_context);
+    let mut sampler = Sampler::new(format_context);
+    let mut has_matches = false;
+    let mut also_results = Vec::new();
+    let input_sources = input_sources(json_files, include_stdin, io::stdin().is_terminal());
+    let mut file_headers = FileHeaders::new(&input_sources, format_context);
+    let any_read = search_inputs(
+        &input_sources,
+        field_path_parts,
+        field_name,
+        search_context,
+        format_context,
+        |input_index, file_path, result| {
+            if !sampler.keep() {
+                return;
+            }
+            has_matches = true;
+            if format_context.also_output.is_some() {
+                buffer_or_exit(
+                    &mut also_results,
+                    Some(file_path),
+                    result.clone(),
+                    format_context,
+                );
+            }
+            if format_context.quiet {
+                return;
+            }
+            let is_new_input = previous_input_index != Some(input_index);
+            previous_input_index = Some(input_index);
+            if is_new_input && format_context.line_numbers_per_file {
+                line_numbers.reset();
+            }
+            for header in file_headers.before_result(input_index) {
+                println!("{}", header);
+            }
+            if format_context.count_distinct {
+                distinct_values.insert(is_new_input, file_path, &result.value);
+            } else if format_context.files_with_matches {
+                // Each file is searched until its first match
+                println!("{}", line_numbers.number(file_path.to_string()));
+            } else if buffer_results {
+                buffer_or_exit(
+                    &mut buffered_results,
+                    Some(file_path),
+                    result,
+                    format_context,
+                );
+            } else if let Some(output) =
+                format_text_line(&result, Some(file_path), format_context, &mut seen_paths)
+                    .and_then(|output| value_runs.push(&result.value, output))
+            {
+                println!("{}", line_numbers.number(output));
+            }
+        },
+    );
+    if require_files && !any_read {
+        let message = format!("none of the {} inputs could be read", input_sources.len());
+        SrchError::new(ErrorKind::NoReadableInput, message, None).exit(format_context.error_format)
+    }
+    if let Some(output) = value_runs.finish() {
+        println!("{}", line_numbers.number(output));
+    }
+    if !format_context.quiet && !search_context.deadline_exceeded() {
+        for header in file_headers.finish() {
+            println!("{}", header);
+        }
+    }
+    if format_context.quiet {
+        // Only the exit code reports the matches
+    } else if format_context.count_distinct {
+        for line in distinct_values.format_counts(input_sources.len() > 1) {
+            println!("{}", line_numbers.number(line));
+        }
+    } else if buffer_results {
+        print_buffered(buffered_results, format_context);
+    }
+    write_also_output(&also_results, format_context);
+    if search_context.deadline_exceeded() {
+        exit_on_timeout(format_context.error_format);
+    }
+    has_matches
+}
+
+/// Writes the results to the --also file in its format.
+fn write_also_output(results: &[(Option<&str>, SearchResult)], format_context: &FormatContext) {
+    let Some((output_format, file_path)) = &format_context.also_output else {
+        return;
+    };
+    let also_context = FormatContext {
+        output_format: *output_format,
+        ..format_context.clone()
+    };
+    let output = format_structured_output(results, &also_context) + "\n";
+    if let Err(e) = fs::write(file_path, output) {
+        SrchError::new(ErrorKind::FileWrite, e, Some(file_path))
+            .report(format_context.error_format);
+    }
+}
+
+/// Keeps only the results whose value occurs at least twice for --duplicates, in their order.
+/// Values are compared by their JSON text, so 1 and "1" are distinct.
+fn keep_duplicates(
+    search_results: Vec<(Option<&str>, SearchResult)>,
+) -> Vec<(Option<&str>, SearchResult)> {
+    let mut value_counts: HashMap<String, usize> = HashMap::new();
+    for (_, result) in &search_results {
+        *value_counts.entry(result.value.to_string()).or_default() += 1;
+    }
+    search_results
+        .into_iter()
+        .filter(|(_, result)| value_counts[&result.value.to_string()] > 1)
+        .collect()
+}
+
+/// Sorts the results of each input for --sort, or with --sort-global across all inputs. The sort
+/// is stable, so results with equal keys stay in document order.
+fn sort_results(
+    search_results: &mut [(Option<&str>, SearchResult)],
+    sort_key: SortKey,
+    sort_global: bool,
 ) {
-    for file_path in json_files {
-        let search_results = process_file(file_path, field_path_parts, field_name, search_context);
-        for result in search_results {
-            let output = format_text_output(&result, Some(file_path), format_context);
-            println!("{}", output);
+    let compare = |(_, a): &(Option<&str>, SearchResult), (_, b): &(Option<&str>, SearchResult)| {
+        match sort_key {
+            SortKey::Path => compare_paths(&a.json_path, &b.json_path),
+            SortKey::Value => value_sort_text(&a.value).cmp(&value_sort_text(&b.value)),
+            SortKey::ValueNumeric => match (a.value.as_f64(), b.value.as_f64()) {
+                (Some(a_number), Some(b_number)) => a_number.total_cmp(&b_number),
+                // Numbers come before the other values, which are sorted as text
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => value_sort_text(&a.value).cmp(&value_sort_text(&b.value)),
+            },
+        }
+    };
+    if sort_global {
+        search_results.sort_by(compare);
+    } else {
+        for file_results in search_results.chunk_by_mut(|(a, _), (b, _)| a == b) {
+            file_results.sort_by(compare);
         }
     }
 }
 
+/// Compares paths segment by segment, array indices by their number, e.g. `items.2` before
+/// `items.10`.
+fn compare_paths(a: &[String], b: &[String]) -> Ordering {
+    let segment_key = |segment: &String| (segment.parse::<usize>().ok(), segment.clone());
+    a.iter().map(segment_key).cmp(b.iter().map(segment_key))
+}
+
+/// Strings are sorted by their content, other values by their JSON text.
+fn value_sort_text(value: &Value) -> String {
+    match value {
+        Value::String(string_value) => string_value.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Prints results collected from all inputs as a table, a tree, in a structured format or, for
+/// --duplicates, as text lines.
+fn print_buffered(
+    search_results: Vec<(Option<&str>, SearchResult)>,
+    format_context: &FormatContext,
+) {
+    let mut search_results = if format_context.duplicates {
+        keep_duplicates(search_results)
+    } else {
+        search_results
+    };
+    if let Some(sort_key) = format_context.sort {
+        sort_results(&mut search_results, sort_key, format_context.sort_global);
+    }
+    if format_context.key_by {
+        let search_results: Vec<SearchResult> = search_results
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect();
+        println!("{}", format_keyed_output(&search_results, format_context));
+        return;
+    }
+    if format_context.output_format != OutputFormat::Text {
+        println!(
+            "{}",
+            format_structured_output(&search_results, format_context)
+        );
+        return;
+    }
+    let rows = if format_context.tree_output || format_context.table_output {
+        let search_results: Vec<SearchResult> = search_results
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect();
+        if format_context.tree_output {
+            format_tree_output(&search_results, format_context)
+        } else {
+            format_table_output(&search_results, format_context)
+        }
+    } else {
+        search_results
+            .iter()
+            .map(|(file_path, result)| format_text_output(result, *file_path, format_context))
+            .collect()
+    };
+    let mut line_numbers = LineNumbers::new(format_context);
+    for row in rows {
+        println!("{}", line_numbers.number(row));
+    }
+}
+
+/// Returns whether any match was found.
 pub fn handle_string_or_stdin_input(
     json_string: &Option<String>,
     field_path_parts: &[&str],
     field_name: &str,
     search_context: &SearchContext,
     format_context: &FormatContext,
-) {
-    let json_input_raw = match json_string {
-        Some(json_str) => json_str.clone(),
-        None => match read_from_stdin() {
-            Ok(stdin_json) => stdin_json,
-            Err(e) => {
-                eprintln!("Error reading from stdin: {}", e);
-                std::process::exit(1);
+) -> bool {
+    let json_input_raw = read_string_or_stdin(
+        json_string,
+        search_context.read_timeout,
+        format_context.error_format,
+    );
+
+    let mut buffered_results = Vec::new();
+    let mut line_numbers = LineNumbers::new(format_context);
+    let mut seen_paths = HashSet::new();
+    let mut distinct_values = HashSet::new();
+    let mut value_runs = ValueRuns::new(format_context);
+    let mut sampler = Sampler::new(format_context);
+    let mut has_matches = false;
+    let mut also_results = Vec::new();
+    let processed = process_input(
+        json_input_raw,
+        None,
+        field_path_parts,
+        field_name,
+        search_context,
+        format_context.error_format,
+        &mut |result| {
+            if !sampler.keep() {
+                return;
+            }
+            has_matches = true;
+            if format_context.also_output.is_some() {
+                buffer_or_exit(&mut also_results, None, result.clone(), format_context);
+            }
+            if format_context.quiet {
+                return;
+            }
+            if format_context.count_distinct {
+                distinct_values.insert(result.value.to_string());
+            } else if format_context.buffers_results() {
+                buffer_or_exit(&mut buffered_results, None, result, format_context);
+            } else if let Some(output) =
+                // path_output is always false for string/stdin
+                format_text_line(&result, None, format_context, &mut seen_paths)
+                        .and_then(|output| value_runs.push(&result.value, output))
+            {
+                println!("{}", line_numbers.number(output));
             }
         },
+    );
+    if let Some(output) = value_runs.finish() {
+        println!("{}", line_numbers.number(output));
+    }
+    if !processed || format_context.quiet {
+        // Parse errors are already reported, and with --quiet only the exit code reports matches
+    } else if format_context.count_distinct {
+        println!("{}", line_numbers.number(distinct_values.len().to_string()));
+    } else if format_context.buffers_results() {
+        print_buffered(buffered_results, format_context);
+    }
+    if processed {
+        write_also_output(&also_results, format_context);
+    }
+    if search_context.deadline_exceeded() {
+        exit_on_timeout(format_context.error_format);
+    }
+    has_matches
+}
+
+/// Prints each input document with the transform applied to the matches.
+pub fn handle_transform_input(
+    json_files: &[String],
+    json_string: &Option<String>,
+    field_path_parts: &[&str],
+    field_name: &str,
+    transform: &Transform,
+    search_context: &SearchContext,
+    format_context: &FormatContext,
+) {
+    let error_format = format_context.error_format;
+    let json_inputs: Box<dyn Iterator<Item = (Option<&str>, String)>> = if !json_files.is_empty() {
+        Box::new(json_files.iter().filter_map(|file_path| {
+            read_file_or_report(file_path, search_context, error_format)
+                .map(|file_content| (Some(file_path.as_str()), file_content))
+        }))
+    } else {
+        Box::new(std::iter::once((
+            None,
+            read_string_or_stdin(json_string, search_context.read_timeout, error_format),
+        )))
     };
+    for (file_path, json_input_raw) in json_inputs {
+        let transformed = process_json_transform(
+            json_input_raw,
+            field_path_parts,
+            field_name,
+            transform,
+            search_context,
+        );
+        // A partially transformed document is never printed
+        if search_context.deadline_exceeded() {
+            exit_on_timeout(error_format);
+        }
+        match transformed {
+            Ok(output) => println!("{}", output),
+            Err(e) => SrchError::new(ErrorKind::JsonParse, e, file_path).report(error_format),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::search_json_value;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use regex::Regex;
+    use serde_json::json;
+    use std::fs;
+    use std::io::Write;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_process_file_modified_window() {
+        let dir_path = std::env::temp_dir().join("srch_test_process_file_modified_window");
+        fs::create_dir_all(&dir_path).unwrap();
+        let old_path = dir_path.join("old.json");
+        let new_path = dir_path.join("new.json");
+        fs::write(&old_path, r#"{"a": "test"}"#).unwrap();
+        fs::write(&new_path, r#"{"a": "test"}"#).unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(&old_path)
+            .unwrap()
+            .set_modified(now - 10 * day)
+            .unwrap();
+
+        let search_regex = Regex::new("test").unwrap();
+        let searched_files = |modified_since, modified_until| {
+            let search_context = SearchContext {
+                modified_since,
+                modified_until,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            [&old_path, &new_path]
+                .into_iter()
+                .filter(|file_path| {
+                    let mut found = false;
+                    process_file(
+                        &InputSource::File(file_path.to_str().unwrap()),
+                        &[],
+                        "a",
+                        &search_context,
+                        ErrorFormat::Text,
+                        &mut |_| found = true,
+                    );
+                    found
+                })
+                .map(|file_path| file_path.file_name().unwrap().to_str().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let found_without_window = searched_files(None, None);
+        let found_since = searched_files(Some(now - day), None);
+        let found_until = searched_files(None, Some(now - day));
+        let found_in_window = searched_files(Some(now - 20 * day), Some(now - 5 * day));
+        fs::remove_dir_all(&dir_path).unwrap();
+        assert_eq!(found_without_window, vec!["old.json", "new.json"]);
+        assert_eq!(found_since, vec!["new.json"]);
+        assert_eq!(found_until, vec!["old.json"]);
+        assert_eq!(found_in_window, vec!["old.json"]);
+    }
+
+    #[test]
+    fn test_process_file_gzip_with_json_extension() {
+        let file_path = std::env::temp_dir().join("srch_test_gzip_with_json_extension.json");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(br#"{"a": "test"}"#).unwrap();
+        fs::write(&file_path, encoder.finish().unwrap()).unwrap();
+
+        let search_regex = Regex::new("test").unwrap();
+        let mut results = Vec::new();
+        process_file(
+            &InputSource::File(file_path.to_str().unwrap()),
+            &[],
+            "a",
+            &SearchContext::with_defaults(&search_regex),
+            ErrorFormat::Text,
+            &mut |result| results.push(result),
+        );
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["a".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_line_numbers_sequential() {
+        let format_context = FormatContext {
+            line_numbers: true,
+            ..FormatContext::with_defaults()
+        };
+        let mut line_numbers = LineNumbers::new(&format_context);
+        assert_eq!(line_numbers.number("a: 1".to_string()), "1: a: 1");
+        assert_eq!(line_numbers.number("b: 2".to_string()), "2: b: 2");
+        assert_eq!(line_numbers.number("c: 3".to_string()), "3: c: 3");
+        line_numbers.reset();
+        assert_eq!(line_numbers.number("d: 4".to_string()), "1: d: 4");
+
+        let mut line_numbers = LineNumbers::new(&FormatContext {
+            line_numbers: false,
+            ..format_context
+        });
+        assert_eq!(line_numbers.number("a: 1".to_string()), "a: 1");
+    }
+
+    #[test]
+    fn test_format_text_line_collapse_indices() {
+        let json_value = json!({"users": [{"name": "a"}, {"name": "b"}, {"name": "c"}]});
+        let search_regex = Regex::new(".").unwrap();
+        let search_results = search_json_value(
+            &json_value,
+            &[],
+            "name",
+            Vec::new(),
+            &SearchContext::with_defaults(&search_regex),
+        )
+        .unwrap();
+        assert_eq!(search_results.len(), 3);
+
+        let format_context = FormatContext {
+            collapse_indices: true,
+            ..FormatContext::with_defaults()
+        };
+        let mut seen_paths = HashSet::new();
+        let lines: Vec<String> = search_results
+            .iter()
+            .filter_map(|result| format_text_line(result, None, &format_context, &mut seen_paths))
+            .collect();
+        assert_eq!(lines, vec!["users[].name"]);
+    }
 
-    if let Some(search_results) =
-        process_json_input(json_input_raw, field_path_parts, field_name, search_context)
-    {
-        for result in search_results {
-            // path_output is always false for string/stdin
-            let output = format_text_output(&result, None, format_context);
-            println!("{}", output);
+    #[test]
+    fn test_buffer_result_max_results() {
+        let json_value = json!({"users": [{"name": "a"}, {"name": "b"}, {"name": "c"}]});
+        let search_regex = Regex::new(".").unwrap();
+        let search_results = search_json_value(
+            &json_value,
+            &[],
+            "name",
+            Vec::new(),
+            &SearchContext::with_defaults(&search_regex),
+        )
+        .unwrap();
+        let buffered = |max_results| {
+            let mut buffered_results = Vec::new();
+            search_results.iter().try_for_each(|result| {
+                buffer_result(&mut buffered_results, None, result.clone(), max_results)
+            })?;
+            Ok::<_, String>(buffered_results.len())
+        };
+        assert_eq!(buffered(None), Ok(3));
+        assert_eq!(buffered(Some(3)), Ok(3));
+        assert_eq!(
+            buffered(Some(2)),
+            Err("more than 2 results to hold in memory, see --max-results".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_text_line_keys_only() {
+        let search_results: Vec<SearchResult> = [
+            vec!["users", "0", "name"],
+            vec!["users", "0", "email"],
+            vec!["users", "1", "name"],
+            vec!["admin", "name"],
+            vec![],
+        ]
+        .into_iter()
+        .map(|json_path| SearchResult {
+            json_path: json_path.into_iter().map(String::from).collect(),
+            value: json!("alice"),
+            ..Default::default()
+        })
+        .collect();
+
+        let format_context = FormatContext {
+            keys_only: true,
+            ..FormatContext::with_defaults()
+        };
+        let mut seen_paths = HashSet::new();
+        let lines: Vec<String> = search_results
+            .iter()
+            .filter_map(|result| format_text_line(result, None, &format_context, &mut seen_paths))
+            .collect();
+        assert_eq!(lines, vec!["name", "email"]);
+    }
+
+    #[test]
+    fn test_sampler_fixed_seed() {
+        let format_context = FormatContext {
+            sample: Some(0.3),
+            seed: Some(42),
+            ..FormatContext::with_defaults()
+        };
+        let sampled = |format_context: &FormatContext| {
+            let mut sampler = Sampler::new(format_context);
+            (0..1000).filter(|_| sampler.keep()).collect::<Vec<_>>()
+        };
+        let first_sample = sampled(&format_context);
+        assert_eq!(first_sample, sampled(&format_context));
+        assert!((200..400).contains(&first_sample.len()));
+        let other_seed = FormatContext {
+            seed: Some(7),
+            ..format_context
+        };
+        assert_ne!(first_sample, sampled(&other_seed));
+
+        let keep_all = FormatContext {
+            sample: Some(1.0),
+            ..other_seed
+        };
+        assert_eq!(sampled(&keep_all).len(), 1000);
+        let keep_none = FormatContext {
+            sample: Some(0.0),
+            ..keep_all
+        };
+        assert!(sampled(&keep_none).is_empty());
+    }
+
+    #[test]
+    fn test_value_runs() {
+        let values = [
+            json!("a"),
+            json!("a"),
+            json!("b"),
+            json!(1),
+            json!(1),
+            json!(1),
+            json!("a"),
+        ];
+        let printed_lines = |uniq, count_runs| {
+            let mut value_runs = ValueRuns {
+                enabled: uniq,
+                count_runs,
+                current: None,
+            };
+            let mut lines: Vec<String> = values
+                .iter()
+                .enumerate()
+                .filter_map(|(i, value)| value_runs.push(value, format!("{}: {}", i, value)))
+                .collect();
+            lines.extend(value_runs.finish());
+            lines
+        };
+        assert_eq!(printed_lines(false, false).len(), values.len());
+        assert_eq!(
+            printed_lines(true, false),
+            vec!["0: \"a\"", "2: \"b\"", "3: 1", "6: \"a\""]
+        );
+        assert_eq!(
+            printed_lines(true, true),
+            vec!["2 0: \"a\"", "1 2: \"b\"", "3 3: 1", "1 6: \"a\""]
+        );
+    }
+
+    #[test]
+    fn test_distinct_values_repeated_records() {
+        let json_value = json!({"orders": [
+            {"status": "open"},
+            {"status": "closed"},
+            {"status": "open"},
+            {"status": "open"},
+            {"status": "pending"},
+            {"status": "closed"}
+        ]});
+        let search_regex = Regex::new(".").unwrap();
+        let search_results = search_json_value(
+            &json_value,
+            &[],
+            "status",
+            Vec::new(),
+            &SearchContext::with_defaults(&search_regex),
+        )
+        .unwrap();
+        assert_eq!(search_results.len(), 6);
+
+        let mut distinct_values = DistinctValues::default();
+        for (i, result) in search_results.iter().enumerate() {
+            distinct_values.insert(i == 0, "a.json", &result.value);
         }
+        distinct_values.insert(true, "b.json", &json!("open"));
+        distinct_values.insert(false, "b.json", &json!("archived"));
+        assert_eq!(
+            distinct_values.format_counts(true),
+            vec!["a.json: 3", "b.json: 2", "total: 4"]
+        );
+        assert_eq!(
+            distinct_values.format_counts(false),
+            vec!["a.json: 3", "b.json: 2"]
+        );
+    }
+
+    #[test]
+    fn test_input_sources_include_stdin() {
+        let json_files = vec!["a.json".to_string(), "b.json".to_string()];
+        assert_eq!(
+            input_sources(&json_files, true, false),
+            vec![
+                InputSource::Stdin,
+                InputSource::File("a.json"),
+                InputSource::File("b.json")
+            ],
+        );
+        // Nothing is piped when stdin is a terminal
+        assert_eq!(
+            input_sources(&json_files, true, true),
+            vec![InputSource::File("a.json"), InputSource::File("b.json")],
+        );
+        assert_eq!(
+            input_sources(&json_files, false, false),
+            vec![InputSource::File("a.json"), InputSource::File("b.json")],
+        );
+        assert_eq!(InputSource::Stdin.label(), "<stdin>");
+    }
+
+    #[test]
+    fn test_process_file_max_file_size() {
+        let file_path = std::env::temp_dir().join("srch_test_max_file_size.json");
+        let file_content = br#"{"a": "test"}"#;
+        fs::write(&file_path, file_content).unwrap();
+
+        let search_regex = Regex::new("test").unwrap();
+        let result_count = |max_file_size| {
+            let search_context = SearchContext {
+                max_file_size: Some(max_file_size),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            let mut result_count = 0;
+            process_file(
+                &InputSource::File(file_path.to_str().unwrap()),
+                &[],
+                "a",
+                &search_context,
+                ErrorFormat::Text,
+                &mut |_| result_count += 1,
+            );
+            result_count
+        };
+        let file_size = file_content.len() as u64;
+        let at_limit = result_count(file_size);
+        let over_limit = result_count(file_size - 1);
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(at_limit, 1);
+        assert_eq!(over_limit, 0);
+    }
+
+    #[test]
+    fn test_handle_file_input_reports_matches() {
+        let file_path =
+            std::env::temp_dir().join("srch_test_handle_file_input_reports_matches.json");
+        fs::write(&file_path, r#"{"a": {"secret": "s3cr3t"}}"#).unwrap();
+        let json_files = [file_path.to_str().unwrap().to_string()];
+        let format_context = FormatContext {
+            output_format: OutputFormat::Json,
+            quiet: true,
+            ..FormatContext::with_defaults()
+        };
+        let has_matches = |field_name| {
+            let search_regex = Regex::new(".").unwrap();
+            let search_context = SearchContext::with_defaults(&search_regex);
+            handle_file_input(
+                &json_files,
+                false,
+                false,
+                &[],
+                field_name,
+                &search_context,
+                &format_context,
+            )
+        };
+        let found = has_matches("secret");
+        let not_found = has_matches("password");
+        fs::remove_file(&file_path).unwrap();
+        assert!(found);
+        assert!(!not_found);
+    }
+
+    #[test]
+    fn test_sort_results() {
+        let result = |file_path, json_path: &str, value| {
+            let json_path = json_path.split('.').map(str::to_string).collect();
+            (
+                Some(file_path),
+                SearchResult {
+                    json_path,
+                    value,
+                    ..Default::default()
+                },
+            )
+        };
+        let search_results = vec![
+            result("a.json", "items.10", json!(9)),
+            result("a.json", "items.2", json!("b")),
+            result("a.json", "items.0", json!(10)),
+            result("b.json", "items.1", json!("a")),
+            result("b.json", "items.0", json!(1.5)),
+        ];
+        let sorted_paths = |sort_key, sort_global| {
+            let mut search_results = search_results.clone();
+            sort_results(&mut search_results, sort_key, sort_global);
+            search_results
+                .into_iter()
+                .map(|(file_path, result)| {
+                    format!("{}:{}", file_path.unwrap(), result.json_path.join("."))
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sorted_paths(SortKey::Path, false),
+            vec![
+                "a.json:items.0",
+                "a.json:items.2",
+                "a.json:items.10",
+                "b.json:items.0",
+                "b.json:items.1"
+            ]
+        );
+        // As text, 10 comes before 9
+        assert_eq!(
+            sorted_paths(SortKey::Value, false),
+            vec![
+                "a.json:items.0",
+                "a.json:items.10",
+                "a.json:items.2",
+                "b.json:items.0",
+                "b.json:items.1"
+            ]
+        );
+        assert_eq!(
+            sorted_paths(SortKey::ValueNumeric, true),
+            vec![
+                "b.json:items.0",
+                "a.json:items.10",
+                "a.json:items.0",
+                "b.json:items.1",
+                "a.json:items.2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_duplicates() {
+        let json_value = json!({"users": [
+            {"email": "ada@example.com"},
+            {"email": "alan@example.com"},
+            {"email": "ada@example.com"},
+            {"email": "grace@example.com"}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let search_results: Vec<(Option<&str>, SearchResult)> = search_json_value(
+            &json_value,
+            &[],
+            "email",
+            Vec::new(),
+            &SearchContext::with_defaults(&search_regex),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|result| (Some("users.json"), result))
+        .collect();
+        let duplicate_paths: Vec<String> = keep_duplicates(search_results)
+            .into_iter()
+            .map(|(_, result)| result.json_path.join("."))
+            .collect();
+        assert_eq!(duplicate_paths, vec!["users.0.email", "users.2.email"]);
+
+        let unique_results = vec![(
+            None,
+            SearchResult {
+                json_path: vec!["a".to_string()],
+                value: json!(1),
+                ..Default::default()
+            },
+        )];
+        assert!(keep_duplicates(unique_results).is_empty());
+    }
+
+    #[test]
+    fn test_handle_file_input_also_output() {
+        let dir_path = std::env::temp_dir().join("srch_test_handle_file_input_also_output");
+        fs::create_dir_all(&dir_path).unwrap();
+        let file_path = dir_path.join("input.json");
+        let also_path = dir_path.join("results.json");
+        fs::write(&file_path, r#"{"a": {"b": 1}, "c": {"b": 2}}"#).unwrap();
+        let json_files = [file_path.to_str().unwrap().to_string()];
+        let format_context = FormatContext {
+            also_output: Some((OutputFormat::Json, also_path.to_str().unwrap().to_string())),
+            ..FormatContext::with_defaults()
+        };
+        let search_regex = Regex::new("2").unwrap();
+        let has_matches = handle_file_input(
+            &json_files,
+            false,
+            false,
+            &[],
+            "b",
+            &SearchContext::with_defaults(&search_regex),
+            &format_context,
+        );
+        let also_output: Value =
+            serde_json::from_str(&fs::read_to_string(&also_path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir_path).unwrap();
+
+        assert!(has_matches);
+        assert_eq!(
+            also_output,
+            serde_json::json!([{"file": json_files[0], "path": ["c", "b"], "value": 2}])
+        );
+    }
+
+    #[test]
+    fn test_process_file_max_per_file() {
+        let dir_path = std::env::temp_dir().join("srch_test_process_file_max_per_file");
+        fs::create_dir_all(&dir_path).unwrap();
+        let file_contents = [
+            r#"[{"a": 1}, {"a": 2}, {"a": 3}, {"a": 4}]"#,
+            r#"[{"a": 5}]"#,
+        ];
+        let file_paths: Vec<String> = file_contents
+            .iter()
+            .enumerate()
+            .map(|(i, file_content)| {
+                let file_path = dir_path.join(format!("{}.json", i));
+                fs::write(&file_path, file_content).unwrap();
+                file_path.to_str().unwrap().to_string()
+            })
+            .collect();
+
+        let search_regex = Regex::new(".").unwrap();
+        let result_counts = |max_per_file| {
+            let search_context = SearchContext {
+                max_per_file,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            file_paths
+                .iter()
+                .map(|file_path| {
+                    let mut result_count = 0;
+                    process_file(
+                        &InputSource::File(file_path),
+                        &[],
+                        "a",
+                        &search_context,
+                        ErrorFormat::Text,
+                        &mut |_| result_count += 1,
+                    );
+                    result_count
+                })
+                .collect::<Vec<_>>()
+        };
+        let unlimited = result_counts(None);
+        let limited = result_counts(Some(2));
+        fs::remove_dir_all(&dir_path).unwrap();
+        assert_eq!(unlimited, vec![4, 1]);
+        assert_eq!(limited, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_search_inputs_first_file() {
+        let dir_path = std::env::temp_dir().join("srch_test_search_inputs_first_file");
+        fs::create_dir_all(&dir_path).unwrap();
+        let file_contents = [r#"{"a": "no"}"#, r#"{"a": "yes"}"#, r#"{"a": "yes"}"#];
+        let file_paths: Vec<String> = file_contents
+            .iter()
+            .enumerate()
+            .map(|(i, file_content)| {
+                let file_path = dir_path.join(format!("{}.json", i));
+                fs::write(&file_path, file_content).unwrap();
+                file_path.to_str().unwrap().to_string()
+            })
+            .collect();
+
+        let search_regex = Regex::new("yes").unwrap();
+        let search_context = SearchContext {
+            single_result_only: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let matching_files = |first_file| {
+            let format_context = FormatContext {
+                files_with_matches: true,
+                first_file,
+                ..FormatContext::with_defaults()
+            };
+            let mut matching_files = Vec::new();
+            search_inputs(
+                &input_sources(&file_paths, false, true),
+                &[],
+                "a",
+                &search_context,
+                &format_context,
+                |_, file_path, _| matching_files.push(file_path.to_string()),
+            );
+            matching_files
+        };
+        let matching_first = matching_files(true);
+        let matching_all = matching_files(false);
+        fs::remove_dir_all(&dir_path).unwrap();
+
+        assert_eq!(matching_first, file_paths[1..2]);
+        assert_eq!(matching_all, file_paths[1..]);
+    }
+
+    #[test]
+    fn test_file_headers() {
+        let input_sources = [
+            InputSource::File("a.json"),
+            InputSource::File("b.json"),
+            InputSource::File("c.json"),
+            InputSource::File("d.json"),
+        ];
+        let format_context = |show_empty| FormatContext {
+            file_header: true,
+            show_empty,
+            ..FormatContext::with_defaults()
+        };
+        // Results of b.json and d.json, the second b.json result gets no header
+        let headers = |show_empty| {
+            let mut file_headers = FileHeaders::new(&input_sources, &format_context(show_empty));
+            let mut headers = Vec::new();
+            for input_index in [1, 1, 3] {
+                headers.push(file_headers.before_result(input_index));
+            }
+            headers.push(file_headers.finish());
+            headers
+        };
+        assert_eq!(
+            headers(false),
+            vec![
+                vec!["--- b.json ---"],
+                vec![],
+                vec!["--- d.json ---"],
+                vec![]
+            ]
+        );
+        assert_eq!(
+            headers(true),
+            vec![
+                vec!["--- a.json ---", "--- b.json ---"],
+                vec![],
+                vec!["--- c.json ---", "--- d.json ---"],
+                vec![]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_inputs_any_read() {
+        let dir_path = std::env::temp_dir().join("srch_test_search_inputs_any_read");
+        fs::create_dir_all(&dir_path).unwrap();
+        let readable_path = dir_path.join("readable.json");
+        fs::write(&readable_path, r#"{"a": "no"}"#).unwrap();
+        let missing_paths: Vec<String> = ["missing_1.json", "missing_2.json"]
+            .iter()
+            .map(|file_name| dir_path.join(file_name).to_str().unwrap().to_string())
+            .collect();
+        let some_readable_paths = [
+            missing_paths[0].clone(),
+            readable_path.to_str().unwrap().to_string(),
+        ];
+
+        let search_regex = Regex::new("yes").unwrap();
+        let search_context = SearchContext::with_defaults(&search_regex);
+        let format_context = FormatContext::with_defaults();
+        let any_read = |file_paths: &[String]| {
+            search_inputs(
+                &input_sources(file_paths, false, true),
+                &[],
+                "a",
+                &search_context,
+                &format_context,
+                |_, _, _| {},
+            )
+        };
+        let all_missing_read = any_read(&missing_paths);
+        let some_readable_read = any_read(&some_readable_paths);
+        fs::remove_dir_all(&dir_path).unwrap();
+
+        assert!(!all_missing_read);
+        // The readable file counts even without matches
+        assert!(some_readable_read);
+    }
+
+    #[test]
+    fn test_read_value_list() {
+        let file_path = std::env::temp_dir().join("srch_test_read_value_list.txt");
+        fs::write(&file_path, "c1\r\nc2\n\n42\nc1\n").unwrap();
+        let value_list = read_value_list(file_path.to_str().unwrap()).unwrap();
+        fs::remove_file(&file_path).unwrap();
+        let expected: HashSet<String> = ["c1", "c2", "42"].iter().map(|v| v.to_string()).collect();
+        assert_eq!(value_list, expected);
+    }
+
+    #[test]
+    fn test_read_with_timeout() {
+        let blocking_read = || {
+            thread::sleep(Duration::from_secs(5));
+            Ok("{}".to_string())
+        };
+        let error = read_with_timeout(blocking_read, Some(Duration::from_millis(20))).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(error.to_string(), "no end of input within 20 ms");
+
+        let read = || Ok("{}".to_string());
+        assert_eq!(
+            read_with_timeout(read, Some(Duration::from_secs(5))).unwrap(),
+            "{}"
+        );
+        assert_eq!(read_with_timeout(read, None).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_read_to_string_decompressed_plain() {
+        let content = read_to_string_decompressed(&br#"{"a": 1}"#[..]).unwrap();
+        assert_eq!(content, r#"{"a": 1}"#);
+    }
+
+    #[test]
+    fn test_read_from_file_line_range() {
+        let file_path = std::env::temp_dir().join("srch_test_read_from_file_line_range.log");
+        let log_lines = [
+            "starting",
+            "{",
+            r#"  "level": "error","#,
+            r#"  "code": 7"#,
+            "}",
+            "stopping",
+        ];
+        fs::write(&file_path, log_lines.join("\n")).unwrap();
+        let file_path_str = file_path.to_str().unwrap();
+        let read_lines = |range| {
+            read_from_file(
+                file_path_str,
+                None,
+                Some(LineRange::from_range(range).unwrap()),
+            )
+            .unwrap()
+        };
+        let middle = read_lines("2:5");
+        let past_end = read_lines("5:100");
+        let after_end = read_lines("7:9");
+        fs::remove_file(&file_path).unwrap();
+
+        let json_value: Value = serde_json::from_str(&middle).unwrap();
+        assert_eq!(json_value, json!({"level": "error", "code": 7}));
+        assert_eq!(past_end, "}\nstopping");
+        assert_eq!(after_end, "");
+    }
+
+    #[test]
+    fn test_read_to_string_decompressed_concatenated_members() {
+        let gzip_member = |content: &[u8]| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(content).unwrap();
+            encoder.finish().unwrap()
+        };
+        let concatenated = [gzip_member(br#"{"a": "#), gzip_member(br#"1}"#)].concat();
+        let content = read_to_string_decompressed(&concatenated[..]).unwrap();
+        assert_eq!(content, r#"{"a": 1}"#);
     }
 }
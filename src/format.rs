@@ -1,9 +1,268 @@
-use crate::parse::SearchResult;
+use std::ops::Range;
 
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::ErrorFormat;
+use crate::parse::{json_type_name, MatchedOn, SearchResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+    Sql,
+    Properties,
+}
+
+/// When to color the output with --highlight-path.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// With `auto`, only output to a terminal is colored.
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Underlined cyan, reset after each highlighted part of a path.
+const HIGHLIGHT_START: &str = "\x1b[4;36m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+
+#[derive(Clone)]
 pub struct FormatContext {
     pub field_path_separator: String,
     pub hide_value: bool,
     pub path_output: bool,
+    pub table_output: bool,
+    pub max_value_width: usize,
+    pub error_format: ErrorFormat,
+    pub line_numbers: bool,
+    pub line_numbers_per_file: bool,
+    pub output_format: OutputFormat,
+    pub collapse_indices: bool,
+    pub typed_values: bool,
+    pub tree_output: bool,
+    pub files_with_matches: bool,
+    pub first_file: bool,
+    pub sibling_values: bool,
+    pub count_distinct: bool,
+    pub uniq: bool,
+    pub count_runs: bool,
+    pub key_by: bool,
+    pub infer_types: bool,
+    pub sql_table: String,
+    pub show_len: bool,
+    pub sample: Option<f64>,
+    pub seed: Option<u64>,
+    pub quiet: bool,
+    /// Format and path of the file written with --also, next to the regular output.
+    pub also_output: Option<(OutputFormat, String)>,
+    pub keys_only: bool,
+    pub max_results: Option<usize>,
+    pub indices_only_path: bool,
+    /// Path regex whose matches are highlighted in the printed path, if coloring is enabled.
+    pub highlight_path: Option<Regex>,
+    pub duplicates: bool,
+    /// Columns of the structured formats from --fields, in order.
+    pub fields: Option<Vec<OutputField>>,
+    pub shell_quote: bool,
+    pub file_header: bool,
+    pub show_empty: bool,
+    pub sort: Option<SortKey>,
+    pub sort_global: bool,
+}
+
+#[cfg(test)]
+impl FormatContext {
+    /// Context with the default options of the command line, so tests only need to set what they
+    /// exercise.
+    pub fn with_defaults() -> Self {
+        FormatContext {
+            field_path_separator: ".".to_string(),
+            hide_value: false,
+            path_output: false,
+            table_output: false,
+            max_value_width: 50,
+            error_format: ErrorFormat::Text,
+            line_numbers: false,
+            line_numbers_per_file: false,
+            output_format: OutputFormat::Text,
+            collapse_indices: false,
+            typed_values: false,
+            tree_output: false,
+            files_with_matches: false,
+            first_file: false,
+            sibling_values: false,
+            count_distinct: false,
+            uniq: false,
+            count_runs: false,
+            key_by: false,
+            infer_types: false,
+            sql_table: "matches".to_string(),
+            show_len: false,
+            sample: None,
+            seed: None,
+            quiet: false,
+            also_output: None,
+            keys_only: false,
+            max_results: None,
+            indices_only_path: false,
+            highlight_path: None,
+            duplicates: false,
+            fields: None,
+            shell_quote: false,
+            file_header: false,
+            show_empty: false,
+            sort: None,
+            sort_global: false,
+        }
+    }
+}
+
+impl FormatContext {
+    /// Whether results are collected from all inputs before printing.
+    pub fn buffers_results(&self) -> bool {
+        self.table_output
+            || self.tree_output
+            || self.key_by
+            || self.duplicates
+            || self.sort.is_some()
+            || self.output_format != OutputFormat::Text
+    }
+}
+
+/// Order of the results for --sort.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SortKey {
+    Path,
+    Value,
+    ValueNumeric,
+}
+
+/// A column of the structured output formats, selected and ordered with --fields.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputField {
+    File,
+    Path,
+    Value,
+    #[value(name = "matched_on")]
+    MatchedOn,
+}
+
+impl OutputField {
+    fn name(self) -> &'static str {
+        match self {
+            OutputField::File => "file",
+            OutputField::Path => "path",
+            OutputField::Value => "value",
+            OutputField::MatchedOn => "matched_on",
+        }
+    }
+}
+
+/// Columns of the JSON and YAML records without --fields.
+const RECORD_FIELDS: [OutputField; 4] = [
+    OutputField::File,
+    OutputField::Path,
+    OutputField::Value,
+    OutputField::MatchedOn,
+];
+
+/// Columns of the SQL statements without --fields.
+const SQL_FIELDS: [OutputField; 2] = [OutputField::Path, OutputField::Value];
+
+/// Builds the record of a result for the JSON and YAML formats with the selected fields in
+/// order. The file and matched_on fields are left out if unknown.
+fn output_record(
+    file_path: Option<&str>,
+    result: &SearchResult,
+    fields: &[OutputField],
+) -> serde_json::Map<String, Value> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let field_value = match field {
+                OutputField::File => Value::from(file_path?),
+                OutputField::Path => Value::from(result.json_path.clone()),
+                OutputField::Value => result.value.clone(),
+                OutputField::MatchedOn => serde_json::to_value(result.matched_on?).ok()?,
+            };
+            Some((field.name().to_string(), field_value))
+        })
+        .collect()
+}
+
+/// Joins the path with the separator, annotating each segment with its type if known. With
+/// --indices-only-path, only the array indices are kept, e.g. `[2][5][3]` for `matrix.2.5.3`, and
+/// `[]` for a path without any.
+fn format_json_path(result: &SearchResult, format_context: &FormatContext) -> String {
+    format_highlighted_json_path(
+        result,
+        format_context,
+        format_context.highlight_path.as_ref(),
+    )
+}
+
+/// Like `format_json_path`, with the matches of `highlight_path` on the joined path highlighted.
+/// The type annotations are left out of the highlight.
+fn format_highlighted_json_path(
+    result: &SearchResult,
+    format_context: &FormatContext,
+    highlight_path: Option<&Regex>,
+) -> String {
+    if format_context.indices_only_path {
+        let indices: String = result
+            .json_path
+            .iter()
+            .filter(|path_part| path_part.parse::<usize>().is_ok())
+            .map(|index| format!("[{}]", index))
+            .collect();
+        return if indices.is_empty() {
+            "[]".to_string()
+        } else {
+            indices
+        };
+    }
+    let separator = &format_context.field_path_separator;
+    let joined_path = result.json_path.join(separator);
+    let highlight_ranges: Vec<Range<usize>> = highlight_path
+        .map(|highlight_path| {
+            highlight_path
+                .find_iter(&joined_path)
+                .map(|highlight_match| highlight_match.range())
+                .collect()
+        })
+        .unwrap_or_default();
+    if result.path_types.len() != result.json_path.len() {
+        return highlight_ranges_in(&joined_path, 0, &highlight_ranges);
+    }
+    let mut annotated_path = String::new();
+    let mut offset = 0;
+    for (index, (path_part, path_type)) in
+        result.json_path.iter().zip(&result.path_types).enumerate()
+    {
+        let segment = if index == 0 {
+            path_part.to_string()
+        } else {
+            format!("{}{}", separator, path_part)
+        };
+        annotated_path += &highlight_ranges_in(&segment, offset, &highlight_ranges);
+        annotated_path += &format!("({})", path_type);
+        offset += segment.len();
+    }
+    annotated_path
 }
 
 pub fn format_text_output(
@@ -11,15 +270,1015 @@ pub fn format_text_output(
     file_path: Option<&str>,
     format_context: &FormatContext,
 ) -> String {
-    if format_context.path_output && file_path.is_some() {
-        file_path.unwrap().to_string()
-    } else if format_context.hide_value {
-        result.json_path.join(&format_context.field_path_separator)
+    let matched_on_suffix = match result.matched_on {
+        Some(MatchedOn::Key) => " [key]",
+        Some(MatchedOn::Value) => " [value]",
+        None => "",
+    };
+    if let (true, Some(file_path)) = (format_context.path_output, file_path) {
+        return file_path.to_string();
+    }
+    let output = if format_context.hide_value {
+        format!(
+            "{}{}",
+            format_json_path(result, format_context),
+            matched_on_suffix
+        )
     } else {
+        let length_suffix = if format_context.show_len {
+            format_value_length(&result.value)
+        } else {
+            String::new()
+        };
         format!(
-            "{}: {}",
-            result.json_path.join(&format_context.field_path_separator),
-            result.value
+            "{}: {}{}{}",
+            format_json_path(result, format_context),
+            format_value(result, format_context),
+            length_suffix,
+            matched_on_suffix
         )
+    };
+    // Siblings follow on indented lines, e.g. "  id: 7"
+    let sibling_lines = result.siblings.iter().map(|(key, value)| {
+        if format_context.sibling_values {
+            format!("\n  {}: {}", key, value)
+        } else {
+            format!("\n  {}", key)
+        }
+    });
+    // The enclosing value with --context follows pretty-printed and indented
+    let context_lines = result.context.iter().flat_map(|context| {
+        let pretty_context = serde_json::to_string_pretty(context).unwrap_or_default();
+        pretty_context
+            .lines()
+            .map(|line| format!("\n  {}", line))
+            .collect::<Vec<_>>()
+    });
+    output + &sibling_lines.collect::<String>() + &context_lines.collect::<String>()
+}
+
+/// Wraps the parts of `text` within the byte `ranges` in the highlight escape codes, e.g. the part
+/// of the path that satisfied --path-regex. The ranges refer to a longer text in which `text`
+/// starts at `offset`, so that a match can span several path segments.
+fn highlight_ranges_in(text: &str, offset: usize, ranges: &[Range<usize>]) -> String {
+    let mut highlighted = String::new();
+    let mut position = 0;
+    for range in ranges {
+        let start = range
+            .start
+            .saturating_sub(offset)
+            .clamp(position, text.len());
+        let end = range.end.saturating_sub(offset).min(text.len());
+        if start >= end {
+            continue;
+        }
+        highlighted += &text[position..start];
+        highlighted += HIGHLIGHT_START;
+        highlighted += &text[start..end];
+        highlighted += HIGHLIGHT_END;
+        position = end;
+    }
+    highlighted + &text[position..]
+}
+
+/// Describes the length of strings by their content and of other values by their JSON text,
+/// e.g. ` (12 bytes, 10 chars)`.
+fn format_value_length(value: &Value) -> String {
+    let text = match value {
+        Value::String(string_value) => string_value.clone(),
+        _ => value.to_string(),
+    };
+    format!(" ({} bytes, {} chars)", text.len(), text.chars().count())
+}
+
+/// Joins the path with every array index replaced by `[]`, e.g. `users[].name`.
+pub fn format_collapsed_path(result: &SearchResult, format_context: &FormatContext) -> String {
+    let mut collapsed_path = String::new();
+    for (i, path_part) in result.json_path.iter().enumerate() {
+        if path_part.parse::<usize>().is_ok() {
+            collapsed_path.push_str("[]");
+        } else {
+            if i > 0 {
+                collapsed_path.push_str(&format_context.field_path_separator);
+            }
+            collapsed_path.push_str(path_part);
+        }
+    }
+    collapsed_path
+}
+
+/// Renders the value as JSON, or as its source text with --raw, prefixed with its type for
+/// --typed-values, e.g. `number:30`, or replaced by its inferred type for --infer-types. With
+/// --shell-quote, strings are quoted by their content and other values by their JSON text.
+fn format_value(result: &SearchResult, format_context: &FormatContext) -> String {
+    let value_text = match &result.raw_token {
+        Some(raw_token) => raw_token.clone(),
+        None => result.value.to_string(),
+    };
+    if format_context.shell_quote {
+        match &result.value {
+            Value::String(string_value) => shell_quote(string_value),
+            _ => shell_quote(&value_text),
+        }
+    } else if format_context.infer_types {
+        infer_type(&result.value)
+    } else if format_context.typed_values {
+        format!("{}:{}", json_type_name(&result.value), value_text)
+    } else {
+        value_text
+    }
+}
+
+/// Quotes the text as a single POSIX shell word, e.g. `'it'\''s'` for `it's`. Everything but
+/// single quotes is literal between single quotes, so those end the quoting, are escaped and
+/// reopen it.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Describes the type of the value for a schema sketch, e.g. `integer` or `array<string>`.
+/// Arrays with elements of several types list them, e.g. `array<integer|string>`.
+pub fn infer_type(value: &Value) -> String {
+    match value {
+        Value::Number(number) if number.is_f64() => "number".to_string(),
+        Value::Number(_) => "integer".to_string(),
+        Value::Array(elements) => {
+            let mut element_types: Vec<String> = elements.iter().map(infer_type).collect();
+            element_types.sort();
+            element_types.dedup();
+            if element_types.is_empty() {
+                "array<unknown>".to_string()
+            } else {
+                format!("array<{}>", element_types.join("|"))
+            }
+        }
+        _ => json_type_name(value).to_string(),
+    }
+}
+
+fn truncate_value(value: &str, max_value_width: usize) -> String {
+    if max_value_width == 0 || value.chars().count() <= max_value_width {
+        value.to_string()
+    } else {
+        let mut truncated: String = value.chars().take(max_value_width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Formats all results as rows with the path column padded to the longest path.
+pub fn format_table_output(
+    results: &[SearchResult],
+    format_context: &FormatContext,
+) -> Vec<String> {
+    let paths: Vec<String> = results
+        .iter()
+        .map(|result| format_json_path(result, format_context))
+        .collect();
+    if format_context.hide_value {
+        return paths;
+    }
+    // The width is measured without the escape codes of the highlight, which take no space
+    let path_widths: Vec<usize> = results
+        .iter()
+        .map(|result| {
+            format_highlighted_json_path(result, format_context, None)
+                .chars()
+                .count()
+        })
+        .collect();
+    let path_width = path_widths.iter().copied().max().unwrap_or(0);
+    paths
+        .into_iter()
+        .zip(path_widths)
+        .zip(results)
+        .map(|((path, width), result)| {
+            format!(
+                "{}{}  {}",
+                path,
+                " ".repeat(path_width - width),
+                truncate_value(
+                    &format_value(result, format_context),
+                    format_context.max_value_width
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Results grouped by shared path prefixes.
+#[derive(Default)]
+struct TreeNode<'a> {
+    children: Vec<(&'a str, TreeNode<'a>)>,
+    result: Option<&'a SearchResult>,
+}
+
+impl<'a> TreeNode<'a> {
+    fn insert(&mut self, result: &'a SearchResult) {
+        let mut node = self;
+        for path_part in &result.json_path {
+            let position = match node.children.iter().position(|(key, _)| key == path_part) {
+                Some(position) => position,
+                None => {
+                    node.children.push((path_part, TreeNode::default()));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[position].1;
+        }
+        node.result = Some(result);
+    }
+
+    fn label(&self, key: &str, format_context: &FormatContext) -> String {
+        match self.result {
+            Some(result) if !format_context.hide_value => {
+                format!("{}: {}", key, format_value(result, format_context))
+            }
+            _ => key.to_string(),
+        }
+    }
+
+    fn render_children(
+        &self,
+        prefix: &str,
+        format_context: &FormatContext,
+        lines: &mut Vec<String>,
+    ) {
+        for (i, (key, child)) in self.children.iter().enumerate() {
+            let (connector, indent) = if i + 1 == self.children.len() {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            lines.push(format!(
+                "{}{}{}",
+                prefix,
+                connector,
+                child.label(key, format_context)
+            ));
+            child.render_children(&format!("{}{}", prefix, indent), format_context, lines);
+        }
+    }
+}
+
+/// Renders all results as an indented tree with shared path prefixes merged.
+pub fn format_tree_output(results: &[SearchResult], format_context: &FormatContext) -> Vec<String> {
+    let mut root = TreeNode::default();
+    for result in results {
+        root.insert(result);
+    }
+    let mut lines = Vec::new();
+    for (key, child) in &root.children {
+        lines.push(child.label(key, format_context));
+        child.render_children("", format_context, &mut lines);
+    }
+    lines
+}
+
+/// Serializes all results as one JSON array or YAML sequence, as SQL INSERT statements or as
+/// Java properties.
+pub fn format_structured_output(
+    results: &[(Option<&str>, SearchResult)],
+    format_context: &FormatContext,
+) -> String {
+    if format_context.output_format == OutputFormat::Properties {
+        let lines = results.iter().map(|(_, result)| {
+            let path = result.json_path.join(&format_context.field_path_separator);
+            format_property(&path, &result.value)
+        });
+        return lines.collect::<Vec<_>>().join("\n");
+    }
+    if format_context.output_format == OutputFormat::Sql {
+        let fields = format_context.fields.as_deref().unwrap_or(&SQL_FIELDS);
+        let statements = results.iter().map(|(file_path, result)| {
+            let (columns, values): (Vec<&str>, Vec<String>) = fields
+                .iter()
+                .filter_map(|field| {
+                    let sql_value = match field {
+                        OutputField::File => sql_string(file_path.as_ref()?),
+                        OutputField::Path => {
+                            sql_string(&result.json_path.join(&format_context.field_path_separator))
+                        }
+                        OutputField::Value => sql_literal(&result.value),
+                        OutputField::MatchedOn => {
+                            sql_literal(&serde_json::to_value(result.matched_on?).ok()?)
+                        }
+                    };
+                    Some((field.name(), sql_value))
+                })
+                .unzip();
+            format_sql_insert(&format_context.sql_table, &columns, &values)
+        });
+        return statements.collect::<Vec<_>>().join("\n");
+    }
+    let fields = format_context.fields.as_deref().unwrap_or(&RECORD_FIELDS);
+    let records: Vec<serde_json::Map<String, Value>> = results
+        .iter()
+        .map(|(file_path, result)| output_record(*file_path, result, fields))
+        .collect();
+    serialize_structured(&records, format_context)
+}
+
+/// Merges the results into one object from their `--key-by` value to the matched value.
+/// Results without a key are skipped, and the last result wins for duplicate keys.
+pub fn format_keyed_output(results: &[SearchResult], format_context: &FormatContext) -> String {
+    let index: serde_json::Map<String, Value> = results
+        .iter()
+        .filter_map(|result| Some((result.index_key.clone()?, result.value.clone())))
+        .collect();
+    if format_context.output_format == OutputFormat::Sql {
+        let statements = index.iter().map(|(key, value)| {
+            format_sql_insert(
+                &format_context.sql_table,
+                &["key", "value"],
+                &[sql_string(key), sql_literal(value)],
+            )
+        });
+        return statements.collect::<Vec<_>>().join("\n");
+    }
+    if format_context.output_format == OutputFormat::Properties {
+        let lines = index.iter().map(|(key, value)| format_property(key, value));
+        return lines.collect::<Vec<_>>().join("\n");
+    }
+    serialize_structured(&index, format_context)
+}
+
+/// Serializes as YAML for `--output yaml` and as pretty JSON otherwise.
+fn serialize_structured(output: &impl Serialize, format_context: &FormatContext) -> String {
+    match format_context.output_format {
+        OutputFormat::Yaml => serde_yaml::to_string(output)
+            .expect("results are always serializable")
+            .trim_end()
+            .to_string(),
+        OutputFormat::Json | OutputFormat::Text | OutputFormat::Sql | OutputFormat::Properties => {
+            serde_json::to_string_pretty(output).expect("results are always serializable")
+        }
+    }
+}
+
+/// Formats a `key=value` line of a Java properties file. Strings are written by their
+/// content, other values by their JSON text.
+fn format_property(key: &str, value: &Value) -> String {
+    let value_text = match value {
+        Value::String(string_value) => string_value.clone(),
+        _ => value.to_string(),
+    };
+    format!(
+        "{}={}",
+        escape_property(key, true),
+        escape_property(&value_text, false)
+    )
+}
+
+/// Escapes backslashes, separators and line breaks. Spaces and comment characters are escaped
+/// where they would otherwise change how the line is read.
+fn escape_property(text: &str, is_key: bool) -> String {
+    let mut escaped = String::new();
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '=' => escaped.push_str("\\="),
+            ':' => escaped.push_str("\\:"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ' ' if is_key || i == 0 => escaped.push_str("\\ "),
+            '#' | '!' if is_key && i == 0 => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_sql_insert(table: &str, columns: &[&str], values: &[String]) -> String {
+    format!(
+        "INSERT INTO {} ({}) VALUES ({});",
+        table,
+        columns.join(", "),
+        values.join(", ")
+    )
+}
+
+/// Quotes the string as a SQL literal, doubling embedded single quotes.
+fn sql_string(string_value: &str) -> String {
+    format!("'{}'", string_value.replace('\'', "''"))
+}
+
+/// Numbers are unquoted, booleans and null become keywords, and arrays and objects are
+/// quoted as JSON text.
+fn sql_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(true) => "TRUE".to_string(),
+        Value::Bool(false) => "FALSE".to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(string_value) => sql_string(string_value),
+        Value::Array(_) | Value::Object(_) => sql_string(&value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_text_output_matched_on() {
+        let format_context = FormatContext::with_defaults();
+        let key_result = SearchResult {
+            json_path: vec!["a".to_string(), "timeout".to_string()],
+            value: json!(30),
+            matched_on: Some(MatchedOn::Key),
+            ..Default::default()
+        };
+        let value_result = SearchResult {
+            json_path: vec!["a".to_string(), "b".to_string()],
+            value: json!("timeout"),
+            matched_on: Some(MatchedOn::Value),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_text_output(&key_result, None, &format_context),
+            "a.timeout: 30 [key]"
+        );
+        assert_eq!(
+            format_text_output(&value_result, None, &format_context),
+            "a.b: \"timeout\" [value]"
+        );
+    }
+
+    #[test]
+    fn test_format_text_output_annotated_types() {
+        let format_context = FormatContext::with_defaults();
+        let result = SearchResult {
+            json_path: vec!["users".to_string(), "0".to_string(), "name".to_string()],
+            value: json!("Max"),
+            path_types: vec!["array", "object", "string"],
+            ..Default::default()
+        };
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "users(array).0(object).name(string): \"Max\""
+        );
+    }
+
+    #[test]
+    fn test_format_table_output_alignment() {
+        let results = vec![
+            SearchResult {
+                json_path: vec!["a".to_string()],
+                value: json!(1),
+                ..Default::default()
+            },
+            SearchResult {
+                json_path: vec!["abc".to_string(), "0".to_string(), "d".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            format_table_output(&results, &FormatContext::with_defaults()),
+            vec!["a        1", "abc.0.d  \"test\""],
+        );
+    }
+
+    #[test]
+    fn test_format_table_output_truncation() {
+        let results = vec![SearchResult {
+            json_path: vec!["a".to_string()],
+            value: json!("a very long value"),
+            ..Default::default()
+        }];
+        assert_eq!(
+            format_table_output(
+                &results,
+                &FormatContext {
+                    max_value_width: 10,
+                    ..FormatContext::with_defaults()
+                }
+            ),
+            vec!["a  \"a very l…"],
+        );
+        assert_eq!(
+            format_table_output(
+                &results,
+                &FormatContext {
+                    max_value_width: 0,
+                    ..FormatContext::with_defaults()
+                }
+            ),
+            vec!["a  \"a very long value\""],
+        );
+    }
+
+    #[test]
+    fn test_format_table_output_highlight_path() {
+        let results = vec![
+            SearchResult {
+                json_path: vec!["users".to_string(), "1".to_string()],
+                value: json!(1),
+                ..Default::default()
+            },
+            SearchResult {
+                json_path: vec!["other".to_string(), "long".to_string()],
+                value: json!(2),
+                ..Default::default()
+            },
+        ];
+        let format_context = FormatContext {
+            highlight_path: Some(Regex::new(r"users").unwrap()),
+            ..FormatContext::with_defaults()
+        };
+        // The escape codes do not count towards the column width
+        assert_eq!(
+            format_table_output(&results, &format_context),
+            vec!["\x1b[4;36musers\x1b[0m.1     1", "other.long  2"],
+        );
+    }
+
+    #[test]
+    fn test_format_structured_output_json() {
+        let results = vec![
+            (
+                Some("a.json"),
+                SearchResult {
+                    json_path: vec!["a".to_string(), "b".to_string()],
+                    value: json!({"c": 1}),
+                    ..Default::default()
+                },
+            ),
+            (
+                None,
+                SearchResult {
+                    json_path: vec!["d".to_string()],
+                    value: json!("e"),
+                    matched_on: Some(MatchedOn::Key),
+                    ..Default::default()
+                },
+            ),
+        ];
+        let format_context = FormatContext {
+            output_format: OutputFormat::Json,
+            ..FormatContext::with_defaults()
+        };
+        let output: Value =
+            serde_json::from_str(&format_structured_output(&results, &format_context)).unwrap();
+        assert_eq!(
+            output,
+            json!([
+                {"file": "a.json", "path": ["a", "b"], "value": {"c": 1}},
+                {"path": ["d"], "value": "e", "matched_on": "key"},
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_format_structured_output_fields() {
+        let results = vec![(
+            Some("a.json"),
+            SearchResult {
+                json_path: vec!["a".to_string(), "b".to_string()],
+                value: json!(1),
+                ..Default::default()
+            },
+        )];
+        let output = |output_format, fields| {
+            let format_context = FormatContext {
+                output_format,
+                fields: Some(fields),
+                ..FormatContext::with_defaults()
+            };
+            format_structured_output(&results, &format_context)
+        };
+        assert_eq!(
+            output(OutputFormat::Json, vec![OutputField::Value]),
+            "[\n  {\n    \"value\": 1\n  }\n]"
+        );
+        assert_eq!(
+            output(
+                OutputFormat::Yaml,
+                vec![OutputField::Value, OutputField::File, OutputField::Path]
+            ),
+            "- value: 1\n  file: a.json\n  path:\n  - a\n  - b"
+        );
+        assert_eq!(
+            output(
+                OutputFormat::Sql,
+                vec![OutputField::Path, OutputField::Value]
+            ),
+            "INSERT INTO matches (path, value) VALUES ('a.b', 1);"
+        );
+    }
+
+    #[test]
+    fn test_format_structured_output_yaml() {
+        let results = vec![
+            (
+                Some("a.json"),
+                SearchResult {
+                    json_path: vec!["a".to_string(), "0".to_string()],
+                    value: json!({"c": [1, 2]}),
+                    ..Default::default()
+                },
+            ),
+            (
+                Some("b.json"),
+                SearchResult {
+                    json_path: vec!["d".to_string()],
+                    value: json!("e: f"),
+                    ..Default::default()
+                },
+            ),
+        ];
+        let format_context = FormatContext {
+            output_format: OutputFormat::Yaml,
+            ..FormatContext::with_defaults()
+        };
+        let output = format_structured_output(&results, &format_context);
+        let parsed: Value = serde_yaml::from_str(&output).unwrap();
+        assert_eq!(
+            parsed,
+            json!([
+                {"file": "a.json", "path": ["a", "0"], "value": {"c": [1, 2]}},
+                {"file": "b.json", "path": ["d"], "value": "e: f"},
+            ]),
+        );
+        assert!(output.starts_with("- file: a.json\n  path:\n  - a\n  - '0'\n"));
+    }
+
+    #[test]
+    fn test_format_text_output_indices_only_path() {
+        let format_context = FormatContext {
+            indices_only_path: true,
+            ..FormatContext::with_defaults()
+        };
+        let matrix = json!([[[0, 1]], [[2], [3, 4, 5]]]);
+        let text_output = |json_path: &[&str]| {
+            let value = json_path.iter().skip(1).fold(&matrix, |value, index| {
+                &value[index.parse::<usize>().unwrap()]
+            });
+            let result = SearchResult {
+                json_path: json_path.iter().map(|part| part.to_string()).collect(),
+                value: value.clone(),
+                ..Default::default()
+            };
+            format_text_output(&result, None, &format_context)
+        };
+        assert_eq!(text_output(&["matrix", "1", "1", "2"]), "[1][1][2]: 5");
+        assert_eq!(text_output(&["matrix", "0", "0", "1"]), "[0][0][1]: 1");
+        assert_eq!(text_output(&["matrix", "1"]), "[1]: [[2],[3,4,5]]");
+        assert_eq!(text_output(&["matrix"]), "[]: [[[0,1]],[[2],[3,4,5]]]");
+    }
+
+    #[test]
+    fn test_format_text_output_highlight_path() {
+        let format_context = FormatContext {
+            highlight_path: Some(Regex::new(r"users\.\d+").unwrap()),
+            ..FormatContext::with_defaults()
+        };
+        let result = SearchResult {
+            json_path: vec!["users".to_string(), "12".to_string(), "name".to_string()],
+            value: json!("ada"),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "\x1b[4;36musers.12\x1b[0m.name: \"ada\""
+        );
+        let annotated_result = SearchResult {
+            path_types: vec!["object", "array", "object"],
+            ..result.clone()
+        };
+        assert_eq!(
+            format_text_output(&annotated_result, None, &format_context),
+            "\x1b[4;36musers\x1b[0m(object)\x1b[4;36m.12\x1b[0m(array).name(object): \"ada\""
+        );
+        assert!(ColorChoice::Always.enabled(false));
+        assert!(!ColorChoice::Auto.enabled(false));
+        assert!(!ColorChoice::Never.enabled(true));
+    }
+
+    #[test]
+    fn test_format_collapsed_path() {
+        let collapsed_path = |json_path: &[&str]| {
+            let result = SearchResult {
+                json_path: json_path.iter().map(|part| part.to_string()).collect(),
+                ..Default::default()
+            };
+            format_collapsed_path(&result, &FormatContext::with_defaults())
+        };
+        assert_eq!(collapsed_path(&["users", "0", "name"]), "users[].name");
+        assert_eq!(collapsed_path(&["a", "12", "3", "b", "c"]), "a[][].b.c");
+        assert_eq!(collapsed_path(&["0", "name"]), "[].name");
+        assert_eq!(collapsed_path(&["a", "b"]), "a.b");
+    }
+
+    #[test]
+    fn test_format_text_output_typed_values() {
+        let format_context = FormatContext {
+            typed_values: true,
+            ..FormatContext::with_defaults()
+        };
+        let typed_output = |value| {
+            let result = SearchResult {
+                json_path: vec!["a".to_string()],
+                value,
+                ..Default::default()
+            };
+            format_text_output(&result, None, &format_context)
+        };
+        assert_eq!(typed_output(json!("active")), r#"a: string:"active""#);
+        assert_eq!(typed_output(json!(30)), "a: number:30");
+        assert_eq!(typed_output(json!(1.5)), "a: number:1.5");
+        assert_eq!(typed_output(json!(true)), "a: boolean:true");
+        assert_eq!(typed_output(json!(null)), "a: null:null");
+        assert_eq!(typed_output(json!("30")), r#"a: string:"30""#);
+    }
+
+    #[test]
+    fn test_format_structured_output_sql() {
+        let result = |json_path: &[&str], value| SearchResult {
+            json_path: json_path.iter().map(|part| part.to_string()).collect(),
+            value,
+            ..Default::default()
+        };
+        let results = vec![
+            (None, result(&["a", "b"], json!("it's"))),
+            (None, result(&["n"], json!(1.5))),
+            (None, result(&["flag"], json!(false))),
+            (None, result(&["empty"], json!(null))),
+            (Some("x'.json"), result(&["list"], json!(["o'k", 2]))),
+        ];
+        let format_context = FormatContext {
+            output_format: OutputFormat::Sql,
+            ..FormatContext::with_defaults()
+        };
+        assert_eq!(
+            format_structured_output(&results, &format_context),
+            [
+                "INSERT INTO matches (path, value) VALUES ('a.b', 'it''s');",
+                "INSERT INTO matches (path, value) VALUES ('n', 1.5);",
+                "INSERT INTO matches (path, value) VALUES ('flag', FALSE);",
+                "INSERT INTO matches (path, value) VALUES ('empty', NULL);",
+                r#"INSERT INTO matches (path, value) VALUES ('list', '["o''k",2]');"#,
+            ]
+            .join("\n"),
+        );
+        let format_context = FormatContext {
+            fields: Some(vec![
+                OutputField::File,
+                OutputField::Path,
+                OutputField::Value,
+            ]),
+            ..format_context
+        };
+        assert_eq!(
+            format_structured_output(&results[3..], &format_context),
+            [
+                "INSERT INTO matches (path, value) VALUES ('empty', NULL);",
+                r#"INSERT INTO matches (file, path, value) VALUES ('x''.json', 'list', '["o''k",2]');"#,
+            ]
+            .join("\n"),
+        );
+    }
+
+    #[test]
+    fn test_format_text_output_show_len() {
+        let format_context = FormatContext {
+            show_len: true,
+            ..FormatContext::with_defaults()
+        };
+        let output_with_len = |value| {
+            let result = SearchResult {
+                json_path: vec!["a".to_string()],
+                value,
+                ..Default::default()
+            };
+            format_text_output(&result, None, &format_context)
+        };
+        assert_eq!(
+            output_with_len(json!("Grüße")),
+            "a: \"Grüße\" (7 bytes, 5 chars)"
+        );
+        assert_eq!(
+            output_with_len(json!("abc")),
+            "a: \"abc\" (3 bytes, 3 chars)"
+        );
+        assert_eq!(output_with_len(json!(1234)), "a: 1234 (4 bytes, 4 chars)");
+        assert_eq!(
+            output_with_len(json!(["é"])),
+            "a: [\"é\"] (6 bytes, 5 chars)"
+        );
+    }
+
+    #[test]
+    fn test_format_structured_output_properties() {
+        let result = |json_path: &[&str], value| SearchResult {
+            json_path: json_path.iter().map(|part| part.to_string()).collect(),
+            value,
+            ..Default::default()
+        };
+        let results = vec![
+            (None, result(&["db", "url"], json!("jdbc:x?a=b"))),
+            (None, result(&["motd"], json!("line one\nline two"))),
+            (None, result(&["port"], json!(5432))),
+            (None, result(&["my key"], json!(" padded"))),
+            (None, result(&["#x"], json!("C:\\dir"))),
+        ];
+        let format_context = FormatContext {
+            output_format: OutputFormat::Properties,
+            ..FormatContext::with_defaults()
+        };
+        assert_eq!(
+            format_structured_output(&results, &format_context),
+            [
+                r"db.url=jdbc\:x?a\=b",
+                r"motd=line one\nline two",
+                "port=5432",
+                r"my\ key=\ padded",
+                r"\#x=C\:\\dir",
+            ]
+            .join("\n"),
+        );
+    }
+
+    #[test]
+    fn test_format_text_output_raw_token() {
+        let format_context = FormatContext::with_defaults();
+        let result = SearchResult {
+            json_path: vec!["price".to_string()],
+            value: json!(2.5),
+            raw_token: Some("2.50".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "price: 2.50"
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("report.json"), "'report.json'");
+        assert_eq!(shell_quote("my report.json"), "'my report.json'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote("line 1\nline 2"), "'line 1\nline 2'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_format_text_output_shell_quote() {
+        let format_context = FormatContext {
+            shell_quote: true,
+            ..FormatContext::with_defaults()
+        };
+        let output = |value| {
+            let result = SearchResult {
+                json_path: vec!["name".to_string()],
+                value,
+                ..Default::default()
+            };
+            format_text_output(&result, None, &format_context)
+        };
+        assert_eq!(output(json!("Ada's file")), r"name: 'Ada'\''s file'");
+        assert_eq!(output(json!([1, 2])), "name: '[1,2]'");
+    }
+
+    #[test]
+    fn test_infer_type() {
+        assert_eq!(infer_type(&json!("active")), "string");
+        assert_eq!(infer_type(&json!(30)), "integer");
+        assert_eq!(infer_type(&json!(-3)), "integer");
+        assert_eq!(infer_type(&json!(1.5)), "number");
+        assert_eq!(infer_type(&json!(true)), "boolean");
+        assert_eq!(infer_type(&json!(null)), "null");
+        assert_eq!(infer_type(&json!({"a": 1})), "object");
+        assert_eq!(infer_type(&json!([])), "array<unknown>");
+        assert_eq!(infer_type(&json!([1, 2])), "array<integer>");
+        assert_eq!(infer_type(&json!(["a", 1, "b"])), "array<integer|string>");
+        assert_eq!(
+            infer_type(&json!([[1], [2, 3], [{"a": 1}]])),
+            "array<array<integer>|array<object>>"
+        );
+
+        let format_context = FormatContext {
+            infer_types: true,
+            ..FormatContext::with_defaults()
+        };
+        let result = SearchResult {
+            json_path: vec!["tags".to_string()],
+            value: json!(["x", "y"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "tags: array<string>"
+        );
+    }
+
+    #[test]
+    fn test_format_tree_output() {
+        let result = |json_path: &[&str], value| SearchResult {
+            json_path: json_path.iter().map(|part| part.to_string()).collect(),
+            value,
+            ..Default::default()
+        };
+        let results = vec![
+            result(&["users", "0", "name"], json!("a")),
+            result(&["users", "0", "age"], json!(3)),
+            result(&["users", "1", "name"], json!("b")),
+            result(&["count"], json!(2)),
+        ];
+        assert_eq!(
+            format_tree_output(&results, &FormatContext::with_defaults()),
+            vec![
+                "users",
+                "├── 0",
+                "│   ├── name: \"a\"",
+                "│   └── age: 3",
+                "└── 1",
+                "    └── name: \"b\"",
+                "count: 2",
+            ],
+        );
+        let format_context = FormatContext {
+            hide_value: true,
+            ..FormatContext::with_defaults()
+        };
+        assert_eq!(
+            format_tree_output(&results[2..], &format_context),
+            vec!["users", "└── 1", "    └── name", "count"],
+        );
+    }
+
+    #[test]
+    fn test_format_keyed_output() {
+        let result = |index_key: Option<&str>, value| SearchResult {
+            value,
+            index_key: index_key.map(|index_key| index_key.to_string()),
+            ..Default::default()
+        };
+        let results = vec![
+            result(Some("u1"), json!("ada")),
+            result(Some("u2"), json!("alan")),
+            result(None, json!("anon")),
+            result(Some("u1"), json!("grace")),
+        ];
+        assert_eq!(
+            format_keyed_output(&results, &FormatContext::with_defaults()),
+            "{\n  \"u1\": \"grace\",\n  \"u2\": \"alan\"\n}",
+        );
+        let format_context = FormatContext {
+            output_format: OutputFormat::Yaml,
+            ..FormatContext::with_defaults()
+        };
+        assert_eq!(
+            format_keyed_output(&results[..2], &format_context),
+            "u1: ada\nu2: alan",
+        );
+    }
+
+    #[test]
+    fn test_format_text_output_context() {
+        let result = SearchResult {
+            json_path: vec!["users".to_string(), "0".to_string(), "name".to_string()],
+            value: json!("Max"),
+            context: Some(json!({"id": 1, "name": "Max"})),
+            ..Default::default()
+        };
+        let format_context = FormatContext::with_defaults();
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "users.0.name: \"Max\"\n  {\n    \"id\": 1,\n    \"name\": \"Max\"\n  }",
+        );
+    }
+
+    #[test]
+    fn test_format_text_output_siblings() {
+        let result = SearchResult {
+            json_path: vec!["user".to_string(), "name".to_string()],
+            value: json!("ada"),
+            siblings: vec![
+                ("id".to_string(), json!(7)),
+                ("tags".to_string(), json!(["x"])),
+            ],
+            ..Default::default()
+        };
+        let format_context = FormatContext::with_defaults();
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "user.name: \"ada\"\n  id\n  tags",
+        );
+        let format_context = FormatContext {
+            sibling_values: true,
+            ..format_context
+        };
+        assert_eq!(
+            format_text_output(&result, None, &format_context),
+            "user.name: \"ada\"\n  id: 7\n  tags: [\"x\"]",
+        );
     }
 }
@@ -1,47 +1,465 @@
-use clap::Parser;
-use format::FormatContext;
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+
+use clap::{CommandFactory, ValueEnum};
+use format::{FormatContext, OutputFormat};
 use regex::Regex;
+use serde_json::Value;
 
 mod cli;
+mod config;
+mod csv_input;
+mod error;
 mod file;
 mod format;
 mod parse;
+mod raw;
 mod syntax;
+mod transform;
+mod value_filter;
+mod watch;
 
 use cli::Cli;
-use file::{handle_file_input, handle_string_or_stdin_input};
-use parse::SearchContext;
-use syntax::parse_search_path;
+use config::parse_with_config;
+use csv_input::InputFormat;
+use error::{ErrorKind, SrchError};
+use file::{
+    handle_file_input, handle_string_or_stdin_input, handle_transform_input, read_value_list,
+};
+use parse::{Aggregate, ArraySample, FieldComparison, SearchContext};
+use syntax::{
+    explain_search, glob_to_regex, parse_date, parse_path_parts, resolve_search_path,
+    split_numeric_prefix, whole_value_regex, AggregateFunction, ComparisonOperator, Divisibility,
+    EpochSearchTerm, IntRange, LineRange, NumericSearchTerm, VersionSearchTerm,
+};
+use transform::Transform;
+use value_filter::ValueFilter;
+use watch::watch_files;
 
 fn main() {
-    let args = Cli::parse();
-    let json_files = args.json_files;
+    let args = parse_with_config();
+    let deadline = args
+        .timeout
+        .map(|timeout| Instant::now() + Duration::from_secs_f64(timeout));
+    // Without a search path or term, the positional arguments shift towards the files
+    let omits_search_path = args.flatten || args.field.is_some();
+    let omits_search_term = args.exists
+        || args.compare.is_some()
+        || args.flatten
+        || args.object_size.is_some()
+        || args.divisible_by.is_some()
+        || args.odd
+        || args.even
+        || args.value_list.is_some()
+        || args.script.is_some()
+        || args.has_non_ascii
+        || args.jq_value.is_some()
+        || args.contains.is_some()
+        || args.int_range.is_some()
+        || args.equals.is_some();
+    let mut positionals: VecDeque<String> = [args.search_path, args.search_term]
+        .into_iter()
+        .flatten()
+        .chain(args.json_files)
+        .collect();
+    let search_path = if omits_search_path {
+        String::new()
+    } else {
+        positionals.pop_front().unwrap_or_default()
+    };
+    let search_term = match (omits_search_term, positionals.pop_front()) {
+        (true, Some(json_file)) => {
+            positionals.push_front(json_file);
+            String::new()
+        }
+        (_, Some(search_term)) => search_term,
+        (true, None) => String::new(),
+        (false, None) => Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "the following required arguments were not provided:\n  <SEARCH_TERM>",
+            )
+            .exit(),
+    };
+    let json_files = Vec::from(positionals);
+    if args.watch && json_files.is_empty() {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--watch requires JSON_FILES to watch",
+            )
+            .exit()
+    }
+    let (search_term, has_numeric_prefix) = split_numeric_prefix(&search_term);
+    let numeric_search = args.numeric_search || has_numeric_prefix;
+    // Checked here instead of by clap, as the `num:` prefix enables numeric search as well
+    if !numeric_search && (!args.all_numeric_terms.is_empty() || args.rel_epsilon != 0.0) {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--all and --rel-epsilon require numeric search with -n or a 'num:' SEARCH_TERM",
+            )
+            .exit()
+    }
+    let search_term = search_term.to_string();
+    let search_term = match args.normalize {
+        Some(normalization_form) => normalization_form.normalize(&search_term),
+        None => search_term,
+    };
+    let search_pattern = if let Some(script) = &args.script {
+        format!(r"\p{{Script={}}}", script)
+    } else if args.glob {
+        glob_to_regex(&search_term)
+    } else if args.fixed_strings {
+        regex::escape(&search_term)
+    } else if args.eq || args.fuzzy {
+        String::new() // The term is compared literally, so the regex stays unused
+    } else {
+        search_term.clone()
+    };
+    let search_pattern = if args.whole_value {
+        whole_value_regex(&search_pattern)
+    } else {
+        search_pattern
+    };
+
+    let also_output = args.also.as_ref().map(|also| {
+        let output_format = match OutputFormat::from_str(&also[0], true) {
+            Ok(OutputFormat::Text) | Err(_) => Cli::command()
+                .error(
+                    clap::error::ErrorKind::InvalidValue,
+                    format!(
+                        "invalid format '{}' for '--also', expected json, yaml, sql or properties",
+                        also[0]
+                    ),
+                )
+                .exit(),
+            Ok(output_format) => output_format,
+        };
+        (output_format, also[1].clone())
+    });
+
+    if args.fields.is_some()
+        && matches!(args.output, OutputFormat::Text | OutputFormat::Properties)
+        && also_output.is_none()
+    {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--fields requires --output json, yaml or sql, or --also",
+            )
+            .exit()
+    }
+
+    let root_path_parts = match &args.root {
+        Some(root) => parse_path_parts(root, &args.field_path_separator),
+        None => Vec::new(),
+    };
+    let exclude_paths: Vec<Vec<&str>> = args
+        .exclude_paths
+        .iter()
+        .map(|exclude_path| parse_path_parts(exclude_path, &args.field_path_separator))
+        .collect();
+    let additional_numeric_terms: Vec<NumericSearchTerm> = args
+        .all_numeric_terms
+        .iter()
+        .map(|numeric_term| {
+            NumericSearchTerm::from_search_term(numeric_term).unwrap_or_else(|| {
+                SrchError::new(ErrorKind::NumericTerm, numeric_term, None).exit(args.error_format)
+            })
+        })
+        .collect();
+
+    let aggregate = args.aggregate.as_ref().map(|aggregate_args| {
+        let (function, fields) = (&aggregate_args[0], &aggregate_args[1]);
+        let function = AggregateFunction::from_str(function, true).unwrap_or_else(|e| {
+            SrchError::new(ErrorKind::Aggregate, e, None).exit(args.error_format)
+        });
+        let term = NumericSearchTerm::from_search_term(&search_term).unwrap_or_else(|| {
+            SrchError::new(ErrorKind::NumericTerm, &search_term, None).exit(args.error_format)
+        });
+        Aggregate {
+            function,
+            fields: fields.split(',').collect(),
+            term,
+        }
+    });
+
+    let field_comparison = args.compare.as_ref().map(|compare_args| {
+        let (field_a, operator, field_b) = (&compare_args[0], &compare_args[1], &compare_args[2]);
+        let operator = ComparisonOperator::from_str(operator).unwrap_or_else(|| {
+            let message = format!("invalid operator '{}'", operator);
+            SrchError::new(ErrorKind::Compare, message, None).exit(args.error_format)
+        });
+        FieldComparison {
+            field_a,
+            operator,
+            field_b,
+        }
+    });
+    let version_term = args.semver.then(|| {
+        VersionSearchTerm::from_search_term(&search_term).unwrap_or_else(|e| {
+            let message = format!("'{}': {}", search_term, e);
+            SrchError::new(ErrorKind::VersionTerm, message, None).exit(args.error_format)
+        })
+    });
+    let epoch_term = (args.epoch || args.epoch_ms).then(|| {
+        EpochSearchTerm::from_search_term(&search_term, args.epoch_ms).unwrap_or_else(|e| {
+            let message = format!("'{}': {}", search_term, e);
+            SrchError::new(ErrorKind::Date, message, None).exit(args.error_format)
+        })
+    });
+    let object_size = args.object_size.as_ref().map(|object_size| {
+        NumericSearchTerm::from_search_term(object_size).unwrap_or_else(|| {
+            SrchError::new(ErrorKind::NumericTerm, object_size, None).exit(args.error_format)
+        })
+    });
+    let divisibility = match (args.divisible_by, args.odd, args.even) {
+        (Some(divisor), _, _) => Some(Divisibility::divisible_by(divisor)),
+        (None, true, _) => Some(Divisibility::odd()),
+        (None, false, true) => Some(Divisibility::even()),
+        (None, false, false) => None,
+    };
+    let int_range = args.int_range.as_ref().map(|range| {
+        IntRange::from_range(range).unwrap_or_else(|e| {
+            let message = format!("'{}': {}", range, e);
+            SrchError::new(ErrorKind::IntRange, message, None).exit(args.error_format)
+        })
+    });
+    let line_range = args.line_range.as_ref().map(|range| {
+        LineRange::from_range(range).unwrap_or_else(|e| {
+            let message = format!("'{}': {}", range, e);
+            SrchError::new(ErrorKind::LineRange, message, None).exit(args.error_format)
+        })
+    });
+    let value_list = args.value_list.as_ref().map(|value_list_path| {
+        read_value_list(value_list_path).unwrap_or_else(|e| {
+            SrchError::new(ErrorKind::FileRead, e, Some(value_list_path)).exit(args.error_format)
+        })
+    });
+    let value_filter = args.jq_value.as_ref().map(|expression| {
+        ValueFilter::parse(expression).unwrap_or_else(|e| {
+            let message = format!("'{}': {}", expression, e);
+            SrchError::new(ErrorKind::ValueFilter, message, None).exit(args.error_format)
+        })
+    });
+    // A plain word like `urgent` is not valid JSON and is taken as a string
+    let contains = args.contains.as_ref().map(|element| {
+        serde_json::from_str(element).unwrap_or_else(|_| Value::String(element.clone()))
+    });
+    let [modified_since, modified_until] = [&args.since, &args.until].map(|date| {
+        date.as_ref().map(|date| {
+            parse_date(date).unwrap_or_else(|e| {
+                let message = format!("'{}': {}", date, e);
+                SrchError::new(ErrorKind::Date, message, None).exit(args.error_format)
+            })
+        })
+    });
+    let equals: Option<Value> = args.equals.as_ref().map(|expected_value| {
+        serde_json::from_str(expected_value).unwrap_or_else(|e| {
+            let message = format!("--equals '{}': {}", expected_value, e);
+            SrchError::new(ErrorKind::JsonParse, message, None).exit(args.error_format)
+        })
+    });
+    let path_filter = args.path_filter.as_ref().map(|path_filter| {
+        Regex::new(path_filter)
+            .unwrap_or_else(|e| SrchError::new(ErrorKind::Regex, e, None).exit(args.error_format))
+    });
+    let path_regex = args.path_regex.as_ref().map(|path_regex| {
+        Regex::new(path_regex)
+            .unwrap_or_else(|e| SrchError::new(ErrorKind::Regex, e, None).exit(args.error_format))
+    });
+    if args.highlight_path && path_regex.is_none() && path_filter.is_none() {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--highlight-path requires --path-regex or --path-filter",
+            )
+            .exit()
+    }
+    let highlight_path = (args.highlight_path && args.color.enabled(io::stdout().is_terminal()))
+        .then(|| path_regex.clone().or(path_filter.clone()))
+        .flatten();
 
-    match Regex::new(&args.search_term) {
+    let mut found_match = false;
+    match Regex::new(&search_pattern) {
         Ok(search_regex) => {
             let search_context = SearchContext {
                 search_regex: &search_regex,
-                single_result_only: args.single,
+                // One match is enough to list the file
+                single_result_only: args.single || args.files_with_matches || args.quiet,
                 field_path_separator: &args.field_path_separator,
-                numeric_search_enabled: args.numeric_search,
+                numeric_search_enabled: numeric_search,
+                root_path_parts: &root_path_parts,
+                coerce_strings: args.coerce_strings,
+                exclude_paths: &exclude_paths,
+                additional_numeric_terms: &additional_numeric_terms,
+                keys_and_values: args.keys_and_values,
+                max_array_depth: args.max_array_depth,
+                max_object_depth: args.max_object_depth,
+                annotate_types: args.annotate_types,
+                deadline,
+                number_kind: args.number_kind,
+                exists_only: args.exists,
+                captures: args.captures,
+                parse_embedded: args.parse_embedded,
+                array_sample: args
+                    .head
+                    .map(ArraySample::Head)
+                    .or(args.tail.map(ArraySample::Tail)),
+                normalization_form: args.normalize,
+                rel_epsilon: args.rel_epsilon,
+                concat_search: args.concat_search,
+                exact_term: args.eq.then_some(search_term.as_str()),
+                fuzzy: args
+                    .fuzzy
+                    .then_some((search_term.as_str(), args.max_distance)),
+                max_file_size: args.max_file_size,
+                max_per_file: args.max_per_file,
+                read_timeout: args.read_timeout.map(Duration::from_millis),
+                modified_since,
+                modified_until,
+                line_range,
+                array_mode: args.array_mode,
+                aggregate,
+                not_types: &args.not_types,
+                path_filter: path_filter.as_ref(),
+                path_regex: path_regex.as_ref(),
+                siblings: args.siblings,
+                field_comparison,
+                flatten: args.flatten,
+                version_term,
+                epoch_term,
+                no_recurse_matched: args.no_recurse_matched,
+                context_depth: args.context.then_some(args.context_depth as usize),
+                object_size,
+                key_by: args.key_by.as_deref(),
+                base64_decode: args.base64_decode,
+                show_decoded: args.show_decoded,
+                divisibility,
+                int_range,
+                strip_prefix: args.strip_prefix,
+                count_matches: args.count_matches,
+                field_ignore_case: args.field_ignore_case,
+                value_list: value_list.as_ref(),
+                invert_match: args.invert_match,
+                raw: args.raw,
+                has_non_ascii: args.has_non_ascii,
+                value_filter: value_filter.as_ref(),
+                contains: contains.as_ref(),
+                equals: equals.as_ref(),
+                match_array_elements: args.match_array_elements,
+                all_elements: args.all_elements,
+                parse_number: args.parse_number,
+                canonical_numbers: args.canonical_numbers,
+                coerce_numeric_strings: args.coerce_numeric_strings,
+                csv_json_column: match args.input {
+                    InputFormat::Csv => args.json_column.as_deref(),
+                    InputFormat::Json => None,
+                },
+            };
+            let parsed_search_path = if args.flatten {
+                Ok((Vec::new(), ""))
+            } else {
+                resolve_search_path(
+                    &search_path,
+                    args.in_path.as_deref(),
+                    args.field.as_deref(),
+                    search_context.field_path_separator,
+                )
             };
-            match parse_search_path(&args.search_path, search_context.field_path_separator) {
+            match parsed_search_path {
+                Ok((field_path_parts, field_name)) if args.explain => {
+                    let explanation = explain_search(
+                        &field_path_parts,
+                        field_name,
+                        search_regex.as_str(),
+                        numeric_search,
+                    );
+                    for line in explanation {
+                        println!("{}", line);
+                    }
+                }
                 Ok((field_path_parts, field_name)) => {
                     let format_context = FormatContext {
                         field_path_separator: args.field_path_separator.clone(),
                         hide_value: args.hide_value,
                         path_output: args.path_output,
+                        table_output: args.table,
+                        max_value_width: args.max_value_width,
+                        error_format: args.error_format,
+                        line_numbers: args.line_numbers,
+                        line_numbers_per_file: args.line_numbers_per_file,
+                        output_format: args.output,
+                        collapse_indices: args.collapse_indices,
+                        typed_values: args.typed_values,
+                        tree_output: args.tree,
+                        files_with_matches: args.files_with_matches,
+                        first_file: args.first_file,
+                        sibling_values: args.sibling_values,
+                        count_distinct: args.count_distinct,
+                        uniq: args.uniq,
+                        count_runs: args.count_runs,
+                        key_by: args.key_by.is_some(),
+                        infer_types: args.infer_types,
+                        sql_table: args.sql_table.clone(),
+                        show_len: args.show_len,
+                        sample: args.sample,
+                        seed: args.seed,
+                        quiet: args.quiet,
+                        also_output: also_output.clone(),
+                        keys_only: args.keys_only,
+                        max_results: args.max_results,
+                        indices_only_path: args.indices_only_path,
+                        highlight_path: highlight_path.clone(),
+                        duplicates: args.duplicates,
+                        sort: args.sort,
+                        sort_global: args.sort_global,
+                        fields: args.fields.clone(),
+                        shell_quote: args.shell_quote,
+                        file_header: args.file_header,
+                        show_empty: args.show_empty,
+                    };
+                    let transform = match &args.replace {
+                        Some(replacement) => Some(Transform::Replace(replacement)),
+                        None if args.delete => Some(Transform::Delete),
+                        None => None,
                     };
-                    if !json_files.is_empty() {
-                        handle_file_input(
+                    if let Some(transform) = transform {
+                        handle_transform_input(
                             &json_files,
+                            &args.json_string,
                             &field_path_parts,
                             field_name,
+                            &transform,
                             &search_context,
                             &format_context,
                         );
+                    } else if !json_files.is_empty() {
+                        let search = || {
+                            handle_file_input(
+                                &json_files,
+                                args.include_stdin,
+                                args.require_files,
+                                &field_path_parts,
+                                field_name,
+                                &search_context,
+                                &format_context,
+                            )
+                        };
+                        if args.watch {
+                            watch_files(&json_files, !args.watch_append, || {
+                                search();
+                            })
+                            .unwrap_or_else(|e| {
+                                SrchError::new(ErrorKind::Watch, e, None).exit(args.error_format)
+                            });
+                        } else {
+                            found_match = search();
+                        }
                     } else {
-                        handle_string_or_stdin_input(
+                        found_match = handle_string_or_stdin_input(
                             &args.json_string,
                             &field_path_parts,
                             field_name,
@@ -50,15 +468,15 @@ fn main() {
                         );
                     }
                 }
-                Err(e) => {
-                    eprintln!("Error parsing search path: {}", e);
-                    std::process::exit(1);
-                }
+                Err(e) => SrchError::new(ErrorKind::SearchPath, e, None).exit(args.error_format),
             }
         }
-        Err(e) => {
-            eprintln!("Error parsing search term as regex: {}", e);
-            std::process::exit(1);
-        }
+        Err(e) => SrchError::new(ErrorKind::Regex, e, None).exit(args.error_format),
+    }
+    if error::error_reported() {
+        std::process::exit(error::ERROR_EXIT_CODE);
+    }
+    if found_match && args.fail_on_match {
+        std::process::exit(1);
     }
 }
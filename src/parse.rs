@@ -1,19 +1,230 @@
-use regex::Regex;
-use serde_json::Value;
+use std::collections::HashSet;
+use std::iter;
+use std::ops::Range;
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::syntax::NumericSearchTerm;
+use base64::Engine;
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+use crate::raw::raw_token;
+use crate::syntax::{
+    parse_formatted_number, AggregateFunction, ComparisonOperator, Divisibility, EpochSearchTerm,
+    IntRange, JsonType, LineRange, NormalizationForm, NumberKind, NumericSearchTerm,
+    VersionSearchTerm,
+};
+use crate::value_filter::ValueFilter;
 
 pub struct SearchContext<'a> {
     pub search_regex: &'a Regex,
     pub single_result_only: bool,
     pub field_path_separator: &'a str,
     pub numeric_search_enabled: bool,
+    pub root_path_parts: &'a [&'a str],
+    pub coerce_strings: bool,
+    pub exclude_paths: &'a [Vec<&'a str>],
+    pub additional_numeric_terms: &'a [NumericSearchTerm],
+    pub keys_and_values: bool,
+    pub max_array_depth: Option<usize>,
+    pub max_object_depth: Option<usize>,
+    pub annotate_types: bool,
+    pub deadline: Option<Instant>,
+    pub number_kind: Option<NumberKind>,
+    pub exists_only: bool,
+    pub captures: bool,
+    pub parse_embedded: bool,
+    pub array_sample: Option<ArraySample>,
+    pub normalization_form: Option<NormalizationForm>,
+    pub rel_epsilon: f64,
+    pub concat_search: bool,
+    pub exact_term: Option<&'a str>,
+    /// Term and maximum Levenshtein distance of strings matching with --fuzzy.
+    pub fuzzy: Option<(&'a str, usize)>,
+    pub max_file_size: Option<u64>,
+    pub read_timeout: Option<Duration>,
+    pub modified_since: Option<SystemTime>,
+    pub modified_until: Option<SystemTime>,
+    pub line_range: Option<LineRange>,
+    pub array_mode: ArrayMode,
+    pub aggregate: Option<Aggregate<'a>>,
+    pub not_types: &'a [JsonType],
+    pub path_filter: Option<&'a Regex>,
+    pub path_regex: Option<&'a Regex>,
+    pub siblings: bool,
+    pub field_comparison: Option<FieldComparison<'a>>,
+    pub flatten: bool,
+    pub version_term: Option<VersionSearchTerm>,
+    pub epoch_term: Option<EpochSearchTerm>,
+    pub no_recurse_matched: bool,
+    /// Levels of enclosing values to print with --context, 1 for the object containing a match.
+    pub context_depth: Option<usize>,
+    pub object_size: Option<NumericSearchTerm>,
+    pub key_by: Option<&'a str>,
+    pub base64_decode: bool,
+    pub show_decoded: bool,
+    pub divisibility: Option<Divisibility>,
+    pub int_range: Option<IntRange>,
+    pub strip_prefix: bool,
+    pub count_matches: bool,
+    pub field_ignore_case: bool,
+    pub value_list: Option<&'a HashSet<String>>,
+    pub invert_match: bool,
+    pub raw: bool,
+    pub has_non_ascii: bool,
+    pub value_filter: Option<&'a ValueFilter>,
+    pub contains: Option<&'a Value>,
+    pub equals: Option<&'a Value>,
+    pub match_array_elements: bool,
+    pub all_elements: bool,
+    pub parse_number: bool,
+    pub canonical_numbers: bool,
+    pub coerce_numeric_strings: bool,
+    pub csv_json_column: Option<&'a str>,
+    pub max_per_file: Option<usize>,
+}
+
+impl SearchContext<'_> {
+    pub fn deadline_exceeded(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+#[cfg(test)]
+impl<'a> SearchContext<'a> {
+    /// Context with all options disabled, so tests only need to set what they exercise.
+    pub fn with_defaults(search_regex: &'a Regex) -> Self {
+        SearchContext {
+            search_regex,
+            single_result_only: false,
+            field_path_separator: ".",
+            numeric_search_enabled: false,
+            root_path_parts: &[],
+            coerce_strings: false,
+            exclude_paths: &[],
+            additional_numeric_terms: &[],
+            keys_and_values: false,
+            max_array_depth: None,
+            max_object_depth: None,
+            annotate_types: false,
+            deadline: None,
+            number_kind: None,
+            exists_only: false,
+            captures: false,
+            parse_embedded: false,
+            array_sample: None,
+            normalization_form: None,
+            rel_epsilon: 0.0,
+            concat_search: false,
+            exact_term: None,
+            fuzzy: None,
+            max_file_size: None,
+            read_timeout: None,
+            modified_since: None,
+            modified_until: None,
+            line_range: None,
+            array_mode: ArrayMode::All,
+            aggregate: None,
+            not_types: &[],
+            path_filter: None,
+            path_regex: None,
+            siblings: false,
+            field_comparison: None,
+            flatten: false,
+            version_term: None,
+            epoch_term: None,
+            no_recurse_matched: false,
+            context_depth: None,
+            object_size: None,
+            key_by: None,
+            base64_decode: false,
+            show_decoded: false,
+            divisibility: None,
+            int_range: None,
+            strip_prefix: false,
+            count_matches: false,
+            field_ignore_case: false,
+            value_list: None,
+            invert_match: false,
+            raw: false,
+            has_non_ascii: false,
+            value_filter: None,
+            contains: None,
+            equals: None,
+            match_array_elements: false,
+            all_elements: false,
+            parse_number: false,
+            canonical_numbers: false,
+            coerce_numeric_strings: false,
+            csv_json_column: None,
+            max_per_file: None,
+        }
+    }
+}
+
+/// Restricts the search to the first or last elements of every array.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArraySample {
+    Head(usize),
+    Tail(usize),
+}
+
+impl ArraySample {
+    fn index_range(&self, len: usize) -> Range<usize> {
+        match *self {
+            ArraySample::Head(count) => 0..count.min(len),
+            ArraySample::Tail(count) => len.saturating_sub(count)..len,
+        }
+    }
+}
+
+/// Condition on the aggregate of several numeric fields of the same object.
+pub struct Aggregate<'a> {
+    pub function: AggregateFunction,
+    pub fields: Vec<&'a str>,
+    pub term: NumericSearchTerm,
+}
+
+/// Comparison between two numeric fields of the same object, e.g. `end < start`.
+pub struct FieldComparison<'a> {
+    pub field_a: &'a str,
+    pub operator: ComparisonOperator,
+    pub field_b: &'a str,
+}
+
+/// Which elements of an array with several matching elements are kept.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ArrayMode {
+    First,
+    All,
+    Last,
+}
+
+/// Whether a result matched on the key name or on the value in `--keys-and-values` mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedOn {
+    Key,
+    Value,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct SearchResult {
     pub json_path: Vec<String>,
     pub value: Value,
+    pub matched_on: Option<MatchedOn>,
+    /// JSON type of the node at each path segment, filled in with `--annotate-types`.
+    pub path_types: Vec<&'static str>,
+    /// Other keys of the enclosing object and their values, filled in with `--siblings`.
+    pub siblings: Vec<(String, Value)>,
+    /// Value of the `--key-by` field in the enclosing object, with strings unquoted.
+    pub index_key: Option<String>,
+    /// Source text of a matched scalar, filled in with `--raw`.
+    pub raw_token: Option<String>,
+    /// The enclosing value `--context-depth` levels above the match, filled in with `--context`.
+    pub context: Option<Value>,
 }
 
 impl SearchResult {
@@ -25,33 +236,187 @@ impl SearchResult {
         SearchResult {
             json_path,
             value: value.clone(),
+            matched_on: None,
+            path_types: Vec::new(),
+            siblings: Vec::new(),
+            index_key: None,
+            raw_token: None,
+            context: None,
         }
     }
 }
 
-fn search_json_value(
+/// Appended to the path segment of a string value whose JSON content is searched.
+pub const EMBEDDED_JSON_MARKER: &str = "~";
+
+/// Number of arrays and objects enclosing the value currently searched.
+#[derive(Debug, Clone, Copy, Default)]
+struct ContainerDepth {
+    arrays: usize,
+    objects: usize,
+}
+
+/// Collects all results of the search, or None if nothing matched.
+pub fn search_json_value(
     json_value: &Value,
     field_path_parts: &[&str],
     field_name: &str,
     current_path: Vec<String>,
     search_context: &SearchContext,
 ) -> Option<Vec<SearchResult>> {
+    let mut results = Vec::new();
+    search_json_value_with(
+        json_value,
+        field_path_parts,
+        field_name,
+        current_path,
+        search_context,
+        &mut |search_result| results.push(search_result),
+    );
+    if !results.is_empty() {
+        Some(results)
+    } else {
+        None
+    }
+}
+
+/// Passes each result to `on_result` as soon as it is found.
+pub fn search_json_value_with(
+    json_value: &Value,
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: Vec<String>,
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+) {
+    search_json_value_at_depth(
+        json_value,
+        field_path_parts,
+        field_name,
+        current_path,
+        ContainerDepth::default(),
+        search_context,
+        on_result,
+    );
+}
+
+/// Returns whether any result was found.
+fn search_json_value_at_depth(
+    json_value: &Value,
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: Vec<String>,
+    depth: ContainerDepth,
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    if search_context.deadline_exceeded() {
+        return false; // Stop the traversal, the caller reports the timeout
+    }
     match json_value {
+        Value::Object(_)
+            if search_context
+                .max_object_depth
+                .is_some_and(|max_object_depth| depth.objects >= max_object_depth) =>
+        {
+            false
+        }
+        Value::Array(_)
+            if search_context
+                .max_array_depth
+                .is_some_and(|max_array_depth| depth.arrays >= max_array_depth) =>
+        {
+            false
+        }
         Value::Object(obj) => search_object(
             obj,
             field_path_parts,
             field_name,
             current_path,
+            ContainerDepth {
+                objects: depth.objects + 1,
+                ..depth
+            },
             search_context,
+            on_result,
         ),
         Value::Array(arr) => search_array(
             arr,
             field_path_parts,
             field_name,
             current_path,
+            ContainerDepth {
+                arrays: depth.arrays + 1,
+                ..depth
+            },
             search_context,
+            on_result,
         ),
-        _ => None, // No further search for primitives
+        Value::String(string_value) if search_context.parse_embedded => search_embedded_json(
+            string_value,
+            field_path_parts,
+            field_name,
+            &current_path,
+            depth,
+            search_context,
+            on_result,
+        )
+        .unwrap_or_else(|| search_leaf(json_value, &current_path, search_context, on_result)),
+        _ => search_leaf(json_value, &current_path, search_context, on_result),
+    }
+}
+
+/// Searches a string value containing a JSON object or array, marking the crossing in the path.
+/// None if the string holds no such JSON.
+fn search_embedded_json(
+    string_value: &str,
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: &[String],
+    depth: ContainerDepth,
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> Option<bool> {
+    let embedded_value = serde_json::from_str::<Value>(string_value).ok()?;
+    if !(embedded_value.is_object() || embedded_value.is_array()) {
+        return None;
+    }
+    let mut embedded_path = current_path.to_vec();
+    if let Some(last_part) = embedded_path.last_mut() {
+        last_part.push_str(EMBEDDED_JSON_MARKER);
+    }
+    Some(search_json_value_at_depth(
+        &embedded_value,
+        field_path_parts,
+        field_name,
+        embedded_path,
+        depth,
+        search_context,
+        on_result,
+    ))
+}
+
+/// Reports every primitive value with --flatten. Otherwise, primitives are not searched further.
+fn search_leaf(
+    value: &Value,
+    current_path: &[String],
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    if !search_context.flatten {
+        return false;
+    }
+    let Some((field_name, parent_path)) = current_path.split_last() else {
+        return false; // The document itself is a primitive
+    };
+    match check_value_match(value, field_name, parent_path, search_context)
+        .filter(|search_result| path_filter_matches(search_result, search_context))
+    {
+        Some(search_result) => {
+            on_result(search_result);
+            true
+        }
+        None => false,
     }
 }
 
@@ -60,68 +425,485 @@ fn search_object(
     field_path_parts: &[&str],
     field_name: &str,
     current_path: Vec<String>,
+    depth: ContainerDepth,
     search_context: &SearchContext,
-) -> Option<Vec<SearchResult>> {
-    let mut results: Vec<SearchResult> = Vec::new();
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    let mut found = false;
     let mut next_path = current_path.clone();
+    // Matches of the object's own fields are reported in document order, each before the values
+    // nested in its field. Matches of the whole object, e.g. with --aggregate, come first. Since
+    // they are checked before recursing, --no-recurse-matched can skip the subtree of a match.
+    let path_len = current_path.len();
+    let mut own_results = check_object_match(
+        obj,
+        field_path_parts,
+        field_name,
+        &current_path,
+        search_context,
+    )
+    .into_iter()
+    .filter(|found_value| path_filter_matches(found_value, search_context))
+    .peekable();
+    let object_matched = own_results
+        .peek()
+        .is_some_and(|found_value| found_value.json_path.len() <= path_len);
+    let object_results = iter::from_fn(|| {
+        own_results.next_if(|found_value| found_value.json_path.len() <= path_len)
+    });
+    if report_results(object_results, &mut found, search_context, on_result) {
+        return true;
+    }
+    if object_matched && search_context.no_recurse_matched {
+        return found; // The fields belong to the matched object
+    }
 
     for (key, value) in obj {
+        let field_matched = own_results
+            .peek()
+            .is_some_and(|found_value| found_value.json_path.get(path_len) == Some(key));
+        let field_results = iter::from_fn(|| {
+            own_results.next_if(|found_value| found_value.json_path.get(path_len) == Some(key))
+        });
+        if report_results(field_results, &mut found, search_context, on_result) {
+            return true;
+        }
+        if field_matched && search_context.no_recurse_matched {
+            continue; // Skip the subtree of the matched field
+        }
         next_path.push(key.clone());
-        if let Some(recursive_results) = search_json_value(
+        if is_excluded(&next_path, search_context.exclude_paths) {
+            next_path.pop();
+            continue; // Prune excluded subtrees
+        }
+        if search_json_value_at_depth(
             value,
             field_path_parts,
             field_name,
             next_path.clone(),
+            depth,
             search_context,
+            on_result,
         ) {
-            results.extend(recursive_results);
+            found = true;
             if search_context.single_result_only {
-                return Some(results); // Early return in single result mode
+                return true; // Early return in single result mode
             }
         }
         next_path.pop(); // Backtrack
     }
 
-    if let Some(found_value) = check_object_match(
-        obj,
-        field_path_parts,
-        field_name,
-        &current_path,
-        search_context,
-    ) {
-        results.push(found_value);
+    report_results(own_results, &mut found, search_context, on_result);
+    found
+}
+
+/// Passes the results to `on_result`, returning whether to stop in single result mode.
+fn report_results(
+    search_results: impl Iterator<Item = SearchResult>,
+    found: &mut bool,
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    for search_result in search_results {
+        on_result(search_result);
+        *found = true;
         if search_context.single_result_only {
-            return Some(results);
+            return true;
         }
     }
-    if !results.is_empty() {
-        Some(results)
+    false
+}
+
+fn check_object_match(
+    obj: &serde_json::Map<String, Value>,
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Vec<SearchResult> {
+    if search_context.flatten {
+        return Vec::new(); // Leaves are reported by search_leaf
+    }
+    if let Some(aggregate) = &search_context.aggregate {
+        return check_aggregate_match(
+            obj,
+            aggregate,
+            field_path_parts,
+            field_name,
+            current_path,
+            search_context,
+        )
+        .into_iter()
+        .collect();
+    }
+    if let Some(field_comparison) = &search_context.field_comparison {
+        return check_field_comparison_match(
+            obj,
+            field_comparison,
+            field_path_parts,
+            field_name,
+            current_path,
+            search_context,
+        )
+        .into_iter()
+        .collect();
+    }
+    if search_context.keys_and_values {
+        return check_keys_and_values_match(
+            obj,
+            field_path_parts,
+            field_name,
+            current_path,
+            search_context,
+        );
+    }
+    if !path_matches(field_path_parts, current_path) {
+        return Vec::new();
+    }
+
+    // Only the field name itself is compared case-insensitively, the field path stays exact
+    let fields: Vec<(&String, &Value)> = if search_context.field_ignore_case {
+        let field_name = field_name.to_lowercase();
+        obj.iter()
+            .filter(|(key, _)| key.to_lowercase() == field_name)
+            .collect()
+    } else {
+        obj.get_key_value(field_name).into_iter().collect()
+    };
+    fields
+        .into_iter()
+        .filter(|(field_name, _)| path_regex_matches(current_path, field_name, search_context))
+        .filter_map(|(field_name, value)| {
+            check_value_match(value, field_name, current_path, search_context)
+                .map(|search_result| (field_name, search_result))
+        })
+        .map(|(field_name, search_result)| {
+            let index_key = search_context
+                .key_by
+                .and_then(|key_by| obj.get(key_by))
+                .map(|key_value| match key_value {
+                    Value::String(string_value) => string_value.clone(),
+                    _ => key_value.to_string(),
+                });
+            if !search_context.siblings {
+                return SearchResult {
+                    index_key,
+                    ..search_result
+                };
+            }
+            let siblings = obj
+                .iter()
+                .filter(|(key, _)| *key != field_name)
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            SearchResult {
+                siblings,
+                index_key,
+                ..search_result
+            }
+        })
+        .collect()
+}
+
+/// Checks every key and value of objects under the whole search path,
+/// producing one result per key match and one per value match.
+fn check_keys_and_values_match(
+    obj: &serde_json::Map<String, Value>,
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Vec<SearchResult> {
+    let search_path = [field_path_parts, &[field_name]].concat();
+    if !path_matches(&search_path, current_path) {
+        return Vec::new();
+    }
+
+    let mut results = Vec::new();
+    for (key, value) in obj {
+        if search_context.search_regex.is_match(key) {
+            let key_result = SearchResult::create(current_path, key, value);
+            if !is_excluded(&key_result.json_path, search_context.exclude_paths) {
+                results.push(SearchResult {
+                    matched_on: Some(MatchedOn::Key),
+                    ..key_result
+                });
+            }
+        }
+        if let Some(value_result) = check_value_match(value, key, current_path, search_context) {
+            results.push(SearchResult {
+                matched_on: Some(MatchedOn::Value),
+                ..value_result
+            });
+        }
+    }
+    results
+}
+
+/// Checks objects under the whole search path, reporting the object itself if the aggregate
+/// of its fields matches. Objects with a missing or non-numeric field are skipped.
+fn check_aggregate_match(
+    obj: &serde_json::Map<String, Value>,
+    aggregate: &Aggregate,
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    let search_path = [field_path_parts, &[field_name]].concat();
+    if !path_matches(&search_path, current_path)
+        || is_excluded(current_path, search_context.exclude_paths)
+    {
+        return None;
+    }
+
+    let numbers = aggregate
+        .fields
+        .iter()
+        .map(|field| numeric_field(obj, field, search_context))
+        .collect::<Option<Vec<f64>>>()?;
+    if aggregate.term.matches(
+        aggregate.function.apply(&numbers),
+        search_context.rel_epsilon,
+    ) {
+        Some(SearchResult {
+            json_path: current_path.to_vec(),
+            value: Value::Object(obj.clone()),
+            ..Default::default()
+        })
     } else {
         None
     }
 }
 
-fn check_object_match(
+/// Reads a value as a number, parsing strings with --coerce-strings or --parse-number.
+fn numeric_value(value: &Value, search_context: &SearchContext) -> Option<f64> {
+    match value {
+        Value::String(string_value) if search_context.parse_number => {
+            parse_formatted_number(string_value)
+        }
+        Value::String(string_value) if search_context.coerce_strings => {
+            string_value.parse::<f64>().ok()
+        }
+        _ => value.as_f64(),
+    }
+}
+
+/// Reads a field as a number, parsing strings with --coerce-strings or --parse-number.
+fn numeric_field(
+    obj: &serde_json::Map<String, Value>,
+    field: &str,
+    search_context: &SearchContext,
+) -> Option<f64> {
+    numeric_value(obj.get(field)?, search_context)
+}
+
+/// Checks objects under the whole search path, reporting the object itself if its two fields
+/// satisfy the comparison. Objects with a missing or non-numeric field are skipped.
+fn check_field_comparison_match(
     obj: &serde_json::Map<String, Value>,
+    field_comparison: &FieldComparison,
     field_path_parts: &[&str],
     field_name: &str,
     current_path: &[String],
     search_context: &SearchContext,
 ) -> Option<SearchResult> {
-    if !path_matches(field_path_parts, current_path) {
+    let search_path = [field_path_parts, &[field_name]].concat();
+    if !path_matches(&search_path, current_path)
+        || is_excluded(current_path, search_context.exclude_paths)
+    {
+        return None;
+    }
+
+    let number_a = numeric_field(obj, field_comparison.field_a, search_context)?;
+    let number_b = numeric_field(obj, field_comparison.field_b, search_context)?;
+    let comparison =
+        NumericSearchTerm::SingleComparison(field_comparison.operator.clone(), number_b);
+    if comparison.matches(number_a, search_context.rel_epsilon) {
+        Some(SearchResult {
+            json_path: current_path.to_vec(),
+            value: Value::Object(obj.clone()),
+            ..Default::default()
+        })
+    } else {
+        None
+    }
+}
+
+fn check_array_match(
+    arr: &[Value],
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    if search_context.flatten || !path_matches(field_path_parts, current_path) {
+        return None;
+    }
+
+    // Integer field names are interpreted as list indices
+    let index = field_name.parse::<usize>().ok()?;
+    if !sampled_index_range(arr, search_context).contains(&index) {
         return None;
     }
+    let value = arr.get(index)?;
+    check_value_match(value, field_name, current_path, search_context)
+}
 
-    let value = obj.get(field_name)?;
+fn check_value_match(
+    value: &Value,
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    if !check_type_match(value, search_context) {
+        return None;
+    }
 
-    if search_context.numeric_search_enabled {
+    let search_result = if search_context.exists_only || search_context.flatten {
+        Some(SearchResult::create(current_path, field_name, value))
+    } else if let Some(value_list) = search_context.value_list {
+        let value_string = match value {
+            Value::String(string_value) => string_value.clone(),
+            _ => value.to_string(),
+        };
+        value_list
+            .contains(&value_string)
+            .then(|| SearchResult::create(current_path, field_name, value))
+    } else if search_context.has_non_ascii {
+        value
+            .as_str()
+            .filter(|string_value| !string_value.is_ascii())
+            .map(|_| SearchResult::create(current_path, field_name, value))
+    } else if let Some(value_filter) = search_context.value_filter {
+        (!value.is_object() && !value.is_array() && value_filter.matches(value))
+            .then(|| SearchResult::create(current_path, field_name, value))
+    } else if let Some(element) = search_context.contains {
+        value
+            .as_array()
+            .filter(|elements| elements.contains(element))
+            .map(|_| SearchResult::create(current_path, field_name, value))
+    } else if let Some(expected_value) = search_context.equals {
+        (value == expected_value).then(|| SearchResult::create(current_path, field_name, value))
+    } else if let Some(exact_term) = search_context.exact_term {
+        check_exact_match(value, exact_term, field_name, current_path, search_context)
+    } else if search_context.concat_search {
+        check_concat_match(value, field_name, current_path, search_context)
+    } else if let Some(int_range) = &search_context.int_range {
+        value
+            .as_number()
+            .filter(|number| int_range.matches(number))
+            .map(|_| SearchResult::create(current_path, field_name, value))
+    } else if let Some(divisibility) = search_context.divisibility {
+        check_divisibility_match(
+            value,
+            divisibility,
+            field_name,
+            current_path,
+            search_context,
+        )
+    } else if let Some(object_size) = &search_context.object_size {
+        value
+            .as_object()
+            .filter(|obj| object_size.matches(obj.len() as f64, 0.0))
+            .map(|_| SearchResult::create(current_path, field_name, value))
+    } else if let Some(version_term) = &search_context.version_term {
+        value
+            .as_str()
+            .filter(|version_str| version_term.matches(version_str))
+            .map(|_| SearchResult::create(current_path, field_name, value))
+    } else if let Some(epoch_term) = &search_context.epoch_term {
+        value
+            .as_number()
+            .filter(|timestamp| epoch_term.matches(timestamp))
+            .map(|_| SearchResult::create(current_path, field_name, value))
+    } else if search_context.numeric_search_enabled {
         check_numeric_match(value, field_name, current_path, search_context)
+    } else if let (Value::Array(elements), true) = (value, search_context.match_array_elements) {
+        check_array_elements_match(elements, value, field_name, current_path, search_context)
     } else {
         check_regex_match(value, field_name, current_path, search_context)
+    };
+    let search_result = if search_context.invert_match {
+        // Like in regex search, only primitive values are candidates
+        let is_primitive = !(value.is_object() || value.is_array());
+        match search_result {
+            None if is_primitive => Some(SearchResult::create(current_path, field_name, value)),
+            _ => None,
+        }
+    } else {
+        search_result
+    }?;
+
+    if is_excluded(&search_result.json_path, search_context.exclude_paths) {
+        None
+    } else {
+        Some(search_result)
+    }
+}
+
+/// Applies the --number-kind and --not-type filters.
+fn check_type_match(value: &Value, search_context: &SearchContext) -> bool {
+    if let Some(number_kind) = search_context.number_kind {
+        match value {
+            Value::Number(number) if number_kind.matches(number) => {}
+            _ => return false,
+        }
     }
+    !search_context
+        .not_types
+        .iter()
+        .any(|not_type| not_type.matches(value))
+}
+
+/// Whether the joined path of a field matches the --path-regex, if given. Unlike --path-filter,
+/// this is checked before the value is compared, so non-matching fields cost no value match.
+fn path_regex_matches(
+    current_path: &[String],
+    field_name: &str,
+    search_context: &SearchContext,
+) -> bool {
+    search_context.path_regex.is_none_or(|path_regex| {
+        let separator = search_context.field_path_separator;
+        let joined_path = if current_path.is_empty() {
+            field_name.to_string()
+        } else {
+            format!(
+                "{}{}{}",
+                current_path.join(separator),
+                separator,
+                field_name
+            )
+        };
+        path_regex.is_match(&joined_path)
+    })
+}
+
+/// Whether the joined path of the result matches the --path-filter regex, if given.
+fn path_filter_matches(search_result: &SearchResult, search_context: &SearchContext) -> bool {
+    search_context.path_filter.is_none_or(|path_filter| {
+        path_filter.is_match(
+            &search_result
+                .json_path
+                .join(search_context.field_path_separator),
+        )
+    })
+}
+
+pub fn is_excluded(current_path: &[String], exclude_paths: &[Vec<&str>]) -> bool {
+    exclude_paths.iter().any(|exclude_path| {
+        !exclude_path.is_empty()
+            && exclude_path.len() <= current_path.len()
+            && exclude_path
+                .iter()
+                .zip(current_path.iter())
+                .all(|(exclude_part, current_part)| {
+                    *exclude_part == "*" || exclude_part == current_part
+                })
+    })
 }
 
-fn path_matches(field_path_parts: &[&str], current_path: &[String]) -> bool {
+pub fn path_matches(field_path_parts: &[&str], current_path: &[String]) -> bool {
     if field_path_parts.is_empty() {
         true
     } else {
@@ -142,8 +924,14 @@ fn check_numeric_match(
     if let Some(numeric_term) =
         NumericSearchTerm::from_search_term(search_context.search_regex.as_str())
     {
-        if let Some(json_num) = value.as_f64() {
-            if numeric_term.matches(json_num) {
+        if let Some(json_num) = numeric_value(value, search_context) {
+            let rel_epsilon = search_context.rel_epsilon;
+            if numeric_term.matches(json_num, rel_epsilon)
+                && search_context
+                    .additional_numeric_terms
+                    .iter()
+                    .all(|additional_term| additional_term.matches(json_num, rel_epsilon))
+            {
                 return Some(SearchResult::create(current_path, field_name, value));
             }
         }
@@ -151,91 +939,2242 @@ fn check_numeric_match(
     None
 }
 
+/// Matches integers, or integer strings with --coerce-strings, by their remainder.
+fn check_divisibility_match(
+    value: &Value,
+    divisibility: Divisibility,
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    let number = match value {
+        Value::Number(number) => Some(number.clone()),
+        Value::String(string_value) if search_context.coerce_strings => {
+            string_value.parse::<serde_json::Number>().ok()
+        }
+        _ => None,
+    }?;
+    divisibility
+        .matches(&number)
+        .then(|| SearchResult::create(current_path, field_name, value))
+}
+
 fn check_regex_match(
     value: &Value,
     field_name: &str,
     current_path: &[String],
     search_context: &SearchContext,
 ) -> Option<SearchResult> {
-    if (value.is_string() || value.is_number() || value.is_boolean())
-        && search_context.search_regex.is_match(&value.to_string())
-    {
-        return Some(SearchResult::create(current_path, field_name, value));
+    if !(value.is_string() || value.is_number() || value.is_boolean()) {
+        return None;
+    }
+    // With --base64-decode, only strings that decode are searched, by their decoded content
+    let decoded_value = match (value, search_context.base64_decode) {
+        (Value::String(string_value), true) => Some(Value::String(decode_base64(string_value)?)),
+        (_, true) => return None,
+        (_, false) => None,
+    };
+    let matched_value = decoded_value.as_ref().unwrap_or(value);
+    // With --coerce-numeric-strings, a string holding exactly a JSON number is matched like that
+    // number, e.g. "30.0" as 30. Other strings stay quoted.
+    let numeric_string = match matched_value {
+        Value::String(string_value) if search_context.coerce_numeric_strings => {
+            serde_json::from_str::<Number>(string_value).ok()
+        }
+        _ => None,
+    };
+    let value_string = if let Some(number) = &numeric_string {
+        canonical_number(number)
+    } else {
+        match (matched_value, search_context.normalization_form) {
+            (Value::String(string_value), Some(normalization_form)) => {
+                Value::String(normalization_form.normalize(string_value)).to_string()
+            }
+            (Value::Number(number), _) if search_context.canonical_numbers => {
+                canonical_number(number)
+            }
+            _ => matched_value.to_string(),
+        }
+    };
+    if search_context.captures {
+        // Strings are captured from their text, not from their quoted JSON form
+        let capture_text = match (matched_value, &numeric_string) {
+            (Value::String(string_value), None) => match search_context.normalization_form {
+                Some(normalization_form) => normalization_form.normalize(string_value),
+                None => string_value.clone(),
+            },
+            _ => value_string,
+        };
+        let captures = search_context.search_regex.captures(&capture_text)?;
+        return Some(SearchResult::create(
+            current_path,
+            field_name,
+            &named_captures_object(search_context.search_regex, &captures),
+        ));
+    }
+    if search_context.count_matches {
+        // The value is replaced by the number of non-overlapping matches
+        let match_count = search_context.search_regex.find_iter(&value_string).count();
+        return (match_count > 0)
+            .then(|| SearchResult::create(current_path, field_name, &Value::from(match_count)));
+    }
+    let is_match = match search_context.fuzzy {
+        Some((fuzzy_term, max_distance)) => {
+            fuzzy_matches(matched_value, fuzzy_term, max_distance, search_context)
+        }
+        None => search_context.search_regex.is_match(&value_string),
+    };
+    if is_match {
+        let result_value = if search_context.show_decoded {
+            matched_value
+        } else {
+            value
+        };
+        return Some(SearchResult::create(current_path, field_name, result_value));
     }
 
     None
 }
 
-fn search_array(
-    arr: &[Value],
-    field_path_parts: &[&str],
-    field_name: &str,
-    current_path: Vec<String>,
+/// Whether the string is at most `max_distance` single-character edits away from the term for
+/// --fuzzy, e.g. "Max" for "Maxx". Other values never match.
+fn fuzzy_matches(
+    value: &Value,
+    fuzzy_term: &str,
+    max_distance: usize,
     search_context: &SearchContext,
-) -> Option<Vec<SearchResult>> {
-    let mut results: Vec<SearchResult> = Vec::new();
+) -> bool {
+    let Value::String(string_value) = value else {
+        return false;
+    };
+    let distance = match search_context.normalization_form {
+        Some(normalization_form) => {
+            strsim::levenshtein(fuzzy_term, &normalization_form.normalize(string_value))
+        }
+        None => strsim::levenshtein(fuzzy_term, string_value),
+    };
+    distance <= max_distance
+}
+
+/// Renders a number for --canonical-numbers and --coerce-numeric-strings. Floats take their
+/// shortest decimal form without an exponent, so `30.0` becomes `30`, `1.50` becomes `1.5` and
+/// `1.5e3` becomes `1500`. Integers keep their JSON text.
+fn canonical_number(number: &Number) -> String {
+    match number.as_f64() {
+        Some(float) if number.is_f64() => float.to_string(),
+        _ => number.to_string(),
+    }
+}
+
+/// Matches the whole array if any element, or with --all-elements every element, matches the
+/// regex. An empty array has no element to match.
+fn check_array_elements_match(
+    elements: &[Value],
+    value: &Value,
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    let element_matches = |element: &Value| {
+        check_regex_match(element, field_name, current_path, search_context).is_some()
+    };
+    let is_match = if search_context.all_elements {
+        !elements.is_empty() && elements.iter().all(element_matches)
+    } else {
+        elements.iter().any(element_matches)
+    };
+    is_match.then(|| SearchResult::create(current_path, field_name, value))
+}
+
+/// Decodes standard base64, replacing invalid UTF-8 in the decoded bytes.
+fn decode_base64(encoded: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Compares strings byte-for-byte with the term, and numbers and booleans by their JSON text.
+fn check_exact_match(
+    value: &Value,
+    exact_term: &str,
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    let is_equal = match (value, search_context.normalization_form) {
+        (Value::String(string_value), Some(normalization_form)) => {
+            normalization_form.normalize(string_value) == exact_term
+        }
+        (Value::String(string_value), None) => string_value == exact_term,
+        (Value::Number(number), _) => number.to_string() == exact_term,
+        (Value::Bool(bool_value), _) => bool_value.to_string() == exact_term,
+        _ => false,
+    };
+    if is_equal {
+        Some(SearchResult::create(current_path, field_name, value))
+    } else {
+        None
+    }
+}
+
+/// Matches the regex against all string leaves under the value, joined by spaces.
+fn check_concat_match(
+    value: &Value,
+    field_name: &str,
+    current_path: &[String],
+    search_context: &SearchContext,
+) -> Option<SearchResult> {
+    let mut string_leaves = Vec::new();
+    collect_string_leaves(value, &mut string_leaves);
+    if search_context
+        .search_regex
+        .is_match(&string_leaves.join(" "))
+    {
+        Some(SearchResult::create(current_path, field_name, value))
+    } else {
+        None
+    }
+}
+
+fn collect_string_leaves<'a>(value: &'a Value, string_leaves: &mut Vec<&'a str>) {
+    match value {
+        Value::String(string_value) => string_leaves.push(string_value),
+        Value::Array(arr) => arr
+            .iter()
+            .for_each(|item| collect_string_leaves(item, string_leaves)),
+        Value::Object(obj) => obj
+            .values()
+            .for_each(|item| collect_string_leaves(item, string_leaves)),
+        _ => {}
+    }
+}
+
+/// Collects the named capture groups into an object. Groups that did not participate are null.
+fn named_captures_object(search_regex: &Regex, captures: &Captures) -> Value {
+    let named_captures: Map<String, Value> = search_regex
+        .capture_names()
+        .flatten()
+        .map(|name| {
+            let captured = captures.name(name).map_or(Value::Null, |capture| {
+                Value::String(capture.as_str().to_string())
+            });
+            (name.to_string(), captured)
+        })
+        .collect();
+    Value::Object(named_captures)
+}
+
+fn sampled_index_range(arr: &[Value], search_context: &SearchContext) -> Range<usize> {
+    match search_context.array_sample {
+        Some(array_sample) => array_sample.index_range(arr.len()),
+        None => 0..arr.len(),
+    }
+}
+
+fn search_array(
+    arr: &[Value],
+    field_path_parts: &[&str],
+    field_name: &str,
+    current_path: Vec<String>,
+    depth: ContainerDepth,
+    search_context: &SearchContext,
+    on_result: &mut dyn FnMut(SearchResult),
+) -> bool {
+    let mut found = false;
+    // The array's own match is reported after its elements, but known up front so that
+    // --no-recurse-matched can skip the subtree of the matched element
+    let array_result = check_array_match(
+        arr,
+        field_path_parts,
+        field_name,
+        &current_path,
+        search_context,
+    )
+    .filter(|found_value| path_filter_matches(found_value, search_context));
+    // Only the first or last matching element is reported, so results wait per element
+    let mut kept_results: Vec<SearchResult> = Vec::new();
+    let index_range = sampled_index_range(arr, search_context);
     for (index, item) in arr.iter().enumerate() {
+        if !index_range.contains(&index) {
+            continue;
+        }
         let mut next_path = current_path.clone();
         next_path.push(index.to_string()); // Add array index to path
-        if let Some(recursive_results) = search_json_value(
-            item,
-            field_path_parts,
-            field_name,
-            next_path,
-            search_context,
-        ) {
-            if search_context.single_result_only {
-                return Some(recursive_results); // Early return in single result mode
+        if is_excluded(&next_path, search_context.exclude_paths) {
+            continue;
+        }
+        if search_context.no_recurse_matched
+            && array_result
+                .as_ref()
+                .is_some_and(|found_value| found_value.json_path == next_path)
+        {
+            continue; // Skip the subtree of the matched element
+        }
+        let item_found = match search_context.array_mode {
+            ArrayMode::All => search_json_value_at_depth(
+                item,
+                field_path_parts,
+                field_name,
+                next_path,
+                depth,
+                search_context,
+                on_result,
+            ),
+            ArrayMode::First | ArrayMode::Last => {
+                let mut item_results = Vec::new();
+                search_json_value_at_depth(
+                    item,
+                    field_path_parts,
+                    field_name,
+                    next_path,
+                    depth,
+                    search_context,
+                    &mut |search_result| item_results.push(search_result),
+                );
+                let item_found = !item_results.is_empty();
+                if item_found {
+                    kept_results = item_results;
+                }
+                item_found
+            }
+        };
+        if item_found {
+            found = true;
+            if search_context.single_result_only || search_context.array_mode == ArrayMode::First {
+                break; // Early return in single result mode
             }
-            results.extend(recursive_results);
         }
     }
+    for search_result in kept_results {
+        on_result(search_result);
+    }
+    if found && search_context.single_result_only {
+        return true;
+    }
 
-    if !results.is_empty() {
-        Some(results)
-    } else {
-        None
+    if let Some(found_value) = array_result {
+        on_result(found_value);
+        found = true;
+    }
+    found
+}
+
+pub fn to_json_pointer(path_parts: &[impl AsRef<str>]) -> String {
+    path_parts
+        .iter()
+        .map(|part| format!("/{}", part.as_ref().replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+pub fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Collects the type of each node along the path, starting below the given value.
+fn path_types(json_value: &Value, json_path: &[String]) -> Vec<&'static str> {
+    let mut current_value = json_value;
+    let mut types = Vec::new();
+    for path_part in json_path {
+        let next_value = match current_value {
+            Value::Object(obj) => obj.get(path_part),
+            Value::Array(arr) => path_part
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| arr.get(index)),
+            _ => None,
+        };
+        match next_value {
+            Some(next_value) => {
+                types.push(json_type_name(next_value));
+                current_value = next_value;
+            }
+            None => break,
+        }
+    }
+    types
+}
+
+/// Looks up the value `depth` levels above the path, or the root if the path is shorter. Paths
+/// into embedded JSON strings have no enclosing value.
+fn enclosing_value<'a>(
+    json_value: &'a Value,
+    json_path: &[String],
+    depth: usize,
+) -> Option<&'a Value> {
+    let ancestor_path = &json_path[..json_path.len().saturating_sub(depth)];
+    json_value.pointer(&to_json_pointer(ancestor_path))
+}
+
+/// Removes the field path of the search path from the start of the result path for
+/// --strip-prefix. Array indices and keys found below it are kept, and a path that would
+/// become empty is left as is.
+fn strip_path_prefix(search_result: &mut SearchResult, field_path_parts: &[&str]) {
+    let prefix_len = field_path_parts.len();
+    if search_result.json_path.len() <= prefix_len
+        || !path_matches(field_path_parts, &search_result.json_path)
+    {
+        return;
+    }
+    search_result.json_path.drain(..prefix_len);
+    if !search_result.path_types.is_empty() {
+        search_result.path_types.drain(..prefix_len);
     }
 }
 
-pub fn process_json_input(
+/// Parses the input and passes each result to `on_result` as soon as it is found.
+/// Returns whether the root exists.
+pub fn process_json_input_with(
     json_input_raw: String,
     field_path_parts: &[&str],
     field_name: &str,
     search_context: &SearchContext,
-) -> Option<Vec<SearchResult>> {
-    match serde_json::from_str(&json_input_raw) {
-        Ok(json_value) => search_json_value(
+    on_result: &mut dyn FnMut(SearchResult),
+) -> Result<bool, serde_json::Error> {
+    let json_value = serde_json::from_str::<Value>(&json_input_raw)?;
+    // Paths are reported relative to the root, so the search starts with an empty path
+    let Some(root_value) = json_value.pointer(&to_json_pointer(search_context.root_path_parts))
+    else {
+        return Ok(false);
+    };
+    search_json_value_with(
+        root_value,
+        field_path_parts,
+        field_name,
+        Vec::new(),
+        search_context,
+        &mut |mut search_result| {
+            if search_context.annotate_types {
+                search_result.path_types = path_types(root_value, &search_result.json_path);
+            }
+            if let Some(context_depth) = search_context.context_depth {
+                search_result.context =
+                    enclosing_value(root_value, &search_result.json_path, context_depth).cloned();
+            }
+            if search_context.raw
+                && !(search_result.value.is_object() || search_result.value.is_array())
+            {
+                let full_path: Vec<&str> = search_context
+                    .root_path_parts
+                    .iter()
+                    .copied()
+                    .chain(search_result.json_path.iter().map(String::as_str))
+                    .collect();
+                search_result.raw_token =
+                    raw_token(&json_input_raw, &full_path).map(str::to_string);
+            }
+            if search_context.strip_prefix {
+                strip_path_prefix(&mut search_result, field_path_parts);
+            }
+            on_result(search_result);
+        },
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use serde_json::json;
+
+    /// Collects all results of the search in the parsed input. None if the root does not exist.
+    fn process_json_input(
+        json_input_raw: String,
+        field_path_parts: &[&str],
+        field_name: &str,
+        search_context: &SearchContext,
+    ) -> Result<Option<Vec<SearchResult>>, serde_json::Error> {
+        let mut search_results = Vec::new();
+        let root_found = process_json_input_with(
+            json_input_raw,
+            field_path_parts,
+            field_name,
+            search_context,
+            &mut |search_result| search_results.push(search_result),
+        )?;
+        if root_found && !search_results.is_empty() {
+            Ok(Some(search_results))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_search_json_value_with_callback_per_match() {
+        let json_value = json!({"a": [{"b": "x1"}, {"b": "y"}, {"b": "x2"}], "b": "x3"});
+        let search_regex = Regex::new("x").unwrap();
+        let search_context = SearchContext::with_defaults(&search_regex);
+        let mut found_paths = Vec::new();
+        search_json_value_with(
+            &json_value,
+            &[],
+            "b",
+            Vec::new(),
+            &search_context,
+            &mut |search_result| found_paths.push(search_result.json_path.join(".")),
+        );
+        assert_eq!(found_paths, vec!["a.0.b", "a.2.b", "b"]);
+
+        let search_context = SearchContext {
+            single_result_only: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let mut callback_count = 0;
+        search_json_value_with(
+            &json_value,
+            &[],
+            "b",
+            Vec::new(),
+            &search_context,
+            &mut |_| callback_count += 1,
+        );
+        assert_eq!(callback_count, 1);
+    }
+
+    #[test]
+    fn test_process_json_input_with_missing_root() {
+        let search_regex = Regex::new(".").unwrap();
+        let search_context = SearchContext {
+            root_path_parts: &["missing"],
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let mut callback_count = 0;
+        let root_found = process_json_input_with(
+            r#"{"a": 1}"#.to_string(),
+            &[],
+            "a",
+            &search_context,
+            &mut |_| callback_count += 1,
+        );
+        assert!(!root_found.unwrap());
+        assert_eq!(callback_count, 0);
+    }
+
+    #[test]
+    fn test_search_json_value_single_match_object() {
+        let json_value = json!({
+            "a": {
+                "b": {
+                    "c": "test"
+                }
+            }
+        });
+        let field_path_parts = &["a", "b"];
+        let field_name = "c";
+        let search_regex = Regex::new("test").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: true,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_single_match_array() {
+        let json_value = json!([
+            {"a": "test1"},
+            {"a": "test2"}
+        ]);
+        let field_path_parts = &[];
+        let field_name = "a";
+        let search_regex = Regex::new("test2").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: true,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["1".to_string(), "a".to_string()],
+                value: json!("test2"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_multiple_matches_object() {
+        let json_value = json!({
+            "a": {
+                "b": "test",
+                "c": "test"
+            }
+        });
+        let field_path_parts = &["a"];
+        let field_name = "b";
+        let search_regex = Regex::new("test").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: false,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["a".to_string(), "b".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_multiple_matches_array() {
+        let json_value = json!([
+            {"a": "test"},
+            {"a": "test"}
+        ]);
+        let field_path_parts = &[];
+        let field_name = "a";
+        let search_regex = Regex::new("test").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: false,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    json_path: vec!["0".to_string(), "a".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["1".to_string(), "a".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_no_match() {
+        let json_value = json!({"a": "value"});
+        let field_path_parts = &[];
+        let field_name = "b";
+        let search_regex = Regex::new("test").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: false,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(results, vec![]);
+    }
+
+    #[test]
+    fn test_search_json_value_field_path_match() {
+        let json_value = json!({"a":{"b":{"c":"test"}}});
+        let field_path_parts = &["a", "b"];
+        let field_name = "c";
+        let search_regex = Regex::new("test").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: false,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_process_json_input_valid() {
+        let json_input = r#"{"a": "test"}"#.to_string();
+        let field_path_parts = &[];
+        let field_name = "a";
+        let search_regex = Regex::new("test").unwrap();
+        let results = process_json_input(
+            json_input,
+            field_path_parts,
+            field_name,
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: false,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap()
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["a".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_process_json_input_invalid() {
+        let json_input = r#"{invalid json"#.to_string();
+        let field_path_parts = &[];
+        let field_name = "a";
+        let search_regex = Regex::new("test").unwrap();
+        let results = process_json_input(
+            json_input,
+            field_path_parts,
+            field_name,
+            &SearchContext {
+                search_regex: &search_regex,
+                single_result_only: false,
+                field_path_separator: ".",
+                numeric_search_enabled: false,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        );
+        assert!(results.is_err());
+    }
+
+    #[test]
+    fn test_process_json_input_root_relative_paths() {
+        let json_input = r#"{"a": {"b": {"c": "test"}}, "c": "test"}"#.to_string();
+        let field_path_parts = &[];
+        let field_name = "c";
+        let search_regex = Regex::new("test").unwrap();
+        let results = process_json_input(
+            json_input,
+            field_path_parts,
+            field_name,
+            &SearchContext {
+                root_path_parts: &["a", "b"],
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap()
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["c".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_process_json_input_strip_prefix() {
+        let json_input =
+            r#"{"users": {"address": [{"city": "Berlin"}, {"zip": {"city": "Bern"}}]}}"#;
+        let search_regex = Regex::new("Ber").unwrap();
+        let found_paths = |strip_prefix, annotate_types| {
+            let search_context = SearchContext {
+                strip_prefix,
+                annotate_types,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            process_json_input(
+                json_input.to_string(),
+                &["users", "address"],
+                "city",
+                &search_context,
+            )
+            .unwrap()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| (result.json_path.join("."), result.path_types))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_paths(false, false),
+            vec![
+                ("users.address.0.city".to_string(), vec![]),
+                ("users.address.1.zip.city".to_string(), vec![])
+            ]
+        );
+        assert_eq!(
+            found_paths(true, false),
+            vec![
+                ("0.city".to_string(), vec![]),
+                ("1.zip.city".to_string(), vec![])
+            ]
+        );
+        assert_eq!(
+            found_paths(true, true)[0],
+            ("0.city".to_string(), vec!["object", "string"])
+        );
+    }
+
+    #[test]
+    fn test_process_json_input_raw() {
+        let json_input =
+            r#"{"a": {"b": {"price": 2.50, "id": 12345678901234567890123, "name": "caf\u00e9"}}}"#;
+        let search_regex = Regex::new(".").unwrap();
+        let raw_tokens = |field_name| {
+            let search_context = SearchContext {
+                raw: true,
+                root_path_parts: &["a"],
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            process_json_input(json_input.to_string(), &[], field_name, &search_context)
+                .unwrap()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.raw_token)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(raw_tokens("price"), vec![Some("2.50".to_string())]);
+        assert_eq!(
+            raw_tokens("id"),
+            vec![Some("12345678901234567890123".to_string())]
+        );
+        assert_eq!(raw_tokens("name"), vec![Some(r#""caf\u00e9""#.to_string())]);
+    }
+
+    #[test]
+    fn test_process_json_input_root_array_index() {
+        let json_input = r#"{"a": [{"c": "test"}, {"c": "test"}]}"#.to_string();
+        let field_path_parts = &[];
+        let field_name = "c";
+        let search_regex = Regex::new("test").unwrap();
+        let results = process_json_input(
+            json_input,
+            field_path_parts,
+            field_name,
+            &SearchContext {
+                root_path_parts: &["a", "1"],
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap()
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["c".to_string()],
+                value: json!("test"),
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_process_json_input_root_missing() {
+        let json_input = r#"{"a": {"c": "test"}}"#.to_string();
+        let field_path_parts = &[];
+        let field_name = "c";
+        let search_regex = Regex::new("test").unwrap();
+        let results = process_json_input(
+            json_input,
+            field_path_parts,
+            field_name,
+            &SearchContext {
+                root_path_parts: &["b"],
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap()
+        .unwrap_or_default();
+        assert_eq!(results, vec![]);
+    }
+
+    #[test]
+    fn test_search_json_value_field_comparison() {
+        let json_value = json!({"events": [
+            {"start": 1, "end": 5},
+            {"start": 7, "end": 3},
+            {"start": 4, "end": 4},
+            {"start": 9},
+            {"start": 9, "end": "2"}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |operator, coerce_strings| {
+            let search_context = SearchContext {
+                field_comparison: Some(FieldComparison {
+                    field_a: "end",
+                    operator,
+                    field_b: "start",
+                }),
+                coerce_strings,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "events", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_paths(ComparisonOperator::LessThan, false),
+            vec!["events.1"]
+        );
+        assert_eq!(
+            found_paths(ComparisonOperator::LessThan, true),
+            vec!["events.1", "events.4"],
+        );
+        assert_eq!(
+            found_paths(ComparisonOperator::GreaterThanOrEqual, false),
+            vec!["events.0", "events.2"],
+        );
+        assert_eq!(
+            found_paths(ComparisonOperator::Equal, false),
+            vec!["events.2"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_object_size() {
+        let json_value = json!({"items": [
+            {"attributes": {"a": 1, "b": 2}},
+            {"attributes": {"a": 1, "b": 2, "c": 3}},
+            {"attributes": {"a": 1, "b": 2, "c": 3, "d": 4}},
+            {"attributes": ["a", "b", "c", "d"]},
+            {"attributes": "abcd"}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |object_size_term| {
+            let search_context = SearchContext {
+                object_size: NumericSearchTerm::from_search_term(object_size_term),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "attributes", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths(">3"), vec!["items.2.attributes"]);
+        assert_eq!(found_paths("<3"), vec!["items.0.attributes"]);
+        assert_eq!(found_paths("==3"), vec!["items.1.attributes"]);
+        assert_eq!(
+            found_paths(">=3"),
+            vec!["items.1.attributes", "items.2.attributes"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_semver() {
+        let json_value = json!({"packages": [
+            {"version": "1.9.0"},
+            {"version": "1.10.0"},
+            {"version": "2.0.0-beta.1"},
+            {"version": "latest"},
+            {"version": 2}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let search_context = SearchContext {
+            version_term: Some(VersionSearchTerm::from_search_term(">1.9.0").unwrap()),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "version", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(
+            found_paths,
+            vec!["packages.1.version", "packages.2.version"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_epoch() {
+        let json_value = json!({"events": [
+            {"created": 1703980800},
+            {"created": 1704153600},
+            {"created": "1704153600"},
+            {"created": 1706745600}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let search_context = SearchContext {
+            epoch_term: Some(EpochSearchTerm::from_search_term(">=2024-01-01", false).unwrap()),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "created", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(found_paths, vec!["events.1.created", "events.3.created"]);
+    }
+
+    #[test]
+    fn test_search_json_value_no_recurse_matched() {
+        let json_value = json!({
+            "error": {"code": 1, "error": {"code": 2}},
+            "retries": [{"error": {"code": 3}}],
+            "items": [{"error": 4}, [{"error": 5}]]
+        });
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |no_recurse_matched| {
+            let search_context = SearchContext {
+                exists_only: true,
+                no_recurse_matched,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "error", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            found_paths(false),
+            vec![
+                "error",
+                "error.error",
+                "retries.0.error",
+                "items.0.error",
+                "items.1.0.error"
+            ]
+        );
+        // The nested error inside the matched error is suppressed
+        assert_eq!(
+            found_paths(true),
+            vec![
+                "error",
+                "retries.0.error",
+                "items.0.error",
+                "items.1.0.error"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_flatten() {
+        let json_value = json!({"a": {"b": 1, "c": [true, null]}, "d": "x", "e": {}});
+        let search_regex = Regex::new("").unwrap();
+        let search_context = SearchContext {
+            flatten: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let found: Vec<(String, Value)> =
+            search_json_value(&json_value, &[], "", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| (result.json_path.join("."), result.value))
+                .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a.b".to_string(), json!(1)),
+                ("a.c.0".to_string(), json!(true)),
+                ("a.c.1".to_string(), json!(null)),
+                ("d".to_string(), json!("x")),
+            ]
+        );
+
+        let search_context = SearchContext {
+            max_object_depth: Some(1),
+            ..search_context
+        };
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(found_paths, vec!["d"]);
+    }
+
+    #[test]
+    fn test_search_json_value_siblings() {
+        let json_value = json!({"user": {"id": 7, "name": "ada", "tags": ["x"]}, "list": ["ada"]});
+        let search_regex = Regex::new("ada").unwrap();
+        let search_context = SearchContext {
+            siblings: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "name", Vec::new(), &search_context);
+        assert_eq!(
+            results,
+            Some(vec![SearchResult {
+                json_path: vec!["user".to_string(), "name".to_string()],
+                value: json!("ada"),
+                siblings: vec![
+                    ("id".to_string(), json!(7)),
+                    ("tags".to_string(), json!(["x"]))
+                ],
+                ..Default::default()
+            }]),
+        );
+        // Array elements have no sibling keys
+        let results = search_json_value(&json_value, &["list"], "0", Vec::new(), &search_context);
+        assert_eq!(results.unwrap()[0].siblings, Vec::new());
+    }
+
+    #[test]
+    fn test_search_json_value_count_matches() {
+        let json_value = json!({"logs": [
+            {"msg": "error: disk error, retrying after error"},
+            {"msg": "all good"},
+            {"msg": "one error"}
+        ]});
+        let search_regex = Regex::new("error").unwrap();
+        let search_context = SearchContext {
+            count_matches: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results: Vec<(String, Value)> =
+            search_json_value(&json_value, &[], "msg", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| (result.json_path.join("."), result.value))
+                .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("logs.0.msg".to_string(), json!(3)),
+                ("logs.2.msg".to_string(), json!(1))
+            ]
+        );
+
+        // Matches do not overlap
+        let search_regex = Regex::new("aa").unwrap();
+        let search_context = SearchContext {
+            count_matches: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(
+            &json!({"a": "aaaaa"}),
+            &[],
+            "a",
+            Vec::new(),
+            &search_context,
+        );
+        assert_eq!(results.unwrap()[0].value, json!(2));
+    }
+
+    #[test]
+    fn test_search_json_value_parse_number() {
+        let json_value = json!({"rows": [
+            {"price": "$1,200.50", "share": "45%"},
+            {"price": "€99", "share": "5%"},
+            {"price": "n/a", "share": 0.5},
+            {"price": 1500, "share": "50 %"}
+        ]});
+        let found_paths = |field_name, numeric_term, parse_number| {
+            let search_regex = Regex::new(numeric_term).unwrap();
+            let search_context = SearchContext {
+                numeric_search_enabled: true,
+                parse_number,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], field_name, Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths("price", ">1000", false), vec!["rows.3.price"]);
+        assert_eq!(
+            found_paths("price", ">1000", true),
+            vec!["rows.0.price", "rows.3.price"]
+        );
+        assert_eq!(
+            found_paths("share", ">0.4", true),
+            vec!["rows.0.share", "rows.2.share", "rows.3.share"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_canonical_numbers() {
+        let json_value: Value = serde_json::from_str(
+            r#"{"rows": [{"n": 30}, {"n": 30.0}, {"n": 3e1}, {"n": 30.5}, {"n": "30.0"}]}"#,
+        )
+        .unwrap();
+        let found_paths = |search_term, canonical_numbers| {
+            let search_regex = Regex::new(search_term).unwrap();
+            let search_context = SearchContext {
+                canonical_numbers,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "n", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths("^30$", false), vec!["rows.0.n"]);
+        assert_eq!(
+            found_paths("^30$", true),
+            vec!["rows.0.n", "rows.1.n", "rows.2.n"]
+        );
+        assert_eq!(found_paths(r"^30\.5$", true), vec!["rows.3.n"]);
+        assert_eq!(canonical_number(&Number::from_f64(1.50).unwrap()), "1.5");
+        assert_eq!(canonical_number(&Number::from(-7)), "-7");
+    }
+
+    #[test]
+    fn test_search_json_value_coerce_numeric_strings() {
+        let json_value = json!({"rows": [
+            {"n": "30"},
+            {"n": "30.0"},
+            {"n": 30},
+            {"n": " 30"},
+            {"n": "30 apples"},
+            {"n": "thirty"}
+        ]});
+        let found_paths = |search_term, coerce_numeric_strings| {
+            let search_regex = Regex::new(search_term).unwrap();
+            let search_context = SearchContext {
+                coerce_numeric_strings,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "n", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths("^30$", false), vec!["rows.2.n"]);
+        assert_eq!(
+            found_paths("^30$", true),
+            vec!["rows.0.n", "rows.1.n", "rows.2.n", "rows.3.n"]
+        );
+        assert_eq!(found_paths("^\"thirty\"$", true), vec!["rows.5.n"]);
+    }
+
+    #[test]
+    fn test_search_json_value_int_range() {
+        let json_value = json!({"items": [
+            {"qty": 1},
+            {"qty": 50.5},
+            {"qty": 100},
+            {"qty": "50"},
+            {"qty": 50}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |range| {
+            let search_context = SearchContext {
+                int_range: Some(IntRange::from_range(range).unwrap()),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "qty", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths("1..100"), vec!["items.0.qty", "items.4.qty"]);
+        assert_eq!(
+            found_paths("1..=100"),
+            vec!["items.0.qty", "items.2.qty", "items.4.qty"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_divisibility() {
+        let json_value = json!({"items": [
+            {"qty": 6},
+            {"qty": 7},
+            {"qty": "12"},
+            {"qty": 4.5},
+            {"qty": true}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |divisibility, coerce_strings| {
+            let search_context = SearchContext {
+                divisibility: Some(divisibility),
+                coerce_strings,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "qty", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_paths(Divisibility::divisible_by(3), false),
+            vec!["items.0.qty"]
+        );
+        assert_eq!(
+            found_paths(Divisibility::divisible_by(3), true),
+            vec!["items.0.qty", "items.2.qty"]
+        );
+        assert_eq!(found_paths(Divisibility::odd(), false), vec!["items.1.qty"]);
+        assert_eq!(
+            found_paths(Divisibility::even(), false),
+            vec!["items.0.qty"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_base64_decode() {
+        // "aGVsbG8gd29ybGQ=" is "hello world" and "Zm9vYmFy" is "foobar"
+        let json_value = json!({"blobs": [
+            {"data": "aGVsbG8gd29ybGQ="},
+            {"data": "Zm9vYmFy"},
+            {"data": "world, not base64!"},
+            {"data": 42}
+        ]});
+        let search_regex = Regex::new("world").unwrap();
+        let results = |show_decoded| {
+            let search_context = SearchContext {
+                base64_decode: true,
+                show_decoded,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "data", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| (result.json_path.join("."), result.value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            results(false),
+            vec![("blobs.0.data".to_string(), json!("aGVsbG8gd29ybGQ="))]
+        );
+        assert_eq!(
+            results(true),
+            vec![("blobs.0.data".to_string(), json!("hello world"))]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_value_list() {
+        let json_value = json!({"orders": [
+            {"customer": "c1"},
+            {"customer": "c2"},
+            {"customer": 3},
+            {"customer": "3"},
+            {"customer": {"id": "c1"}}
+        ]});
+        let value_list: HashSet<String> = ["c1", "3"].iter().map(|v| v.to_string()).collect();
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |invert_match| {
+            let search_context = SearchContext {
+                value_list: Some(&value_list),
+                invert_match,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "customer", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_paths(false),
+            vec![
+                "orders.0.customer",
+                "orders.2.customer",
+                "orders.3.customer"
+            ]
+        );
+        assert_eq!(found_paths(true), vec!["orders.1.customer"]);
+    }
+
+    #[test]
+    fn test_search_json_value_match_array_elements() {
+        let json_value = json!({"posts": [
+            {"tags": ["rust", "cli"]},
+            {"tags": ["rust", "rustc"]},
+            {"tags": ["go"]},
+            {"tags": []},
+            {"tags": "rust"}
+        ]});
+        let search_regex = Regex::new("rust").unwrap();
+        let found_paths = |match_array_elements, all_elements| {
+            let search_context = SearchContext {
+                match_array_elements,
+                all_elements,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "tags", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths(false, false), vec!["posts.4.tags"]);
+        assert_eq!(
+            found_paths(true, false),
+            vec!["posts.0.tags", "posts.1.tags", "posts.4.tags"]
+        );
+        assert_eq!(
+            found_paths(true, true),
+            vec!["posts.1.tags", "posts.4.tags"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_contains() {
+        let json_value = json!({"tickets": [
+            {"tags": ["urgent", "bug"]},
+            {"tags": ["urgent-ish", 7]},
+            {"tags": ["feature", 7.0]},
+            {"tags": []},
+            {"tags": "urgent"}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |element: Value| {
+            let search_context = SearchContext {
+                contains: Some(&element),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "tags", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths(json!("urgent")), vec!["tickets.0.tags"]);
+        assert_eq!(found_paths(json!(7)), vec!["tickets.1.tags"]);
+        assert!(found_paths(json!("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_search_json_value_fuzzy() {
+        let json_value = json!({"people": [
+            {"name": "Max"},
+            {"name": "Maxx"},
+            {"name": "Maximilian"},
+            {"name": "Alex"},
+            {"name": 1}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |max_distance| {
+            let search_context = SearchContext {
+                fuzzy: Some(("Maxx", max_distance)),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "name", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(found_paths(0), vec!["people.1.name"]);
+        assert_eq!(found_paths(1), vec!["people.0.name", "people.1.name"]);
+        assert_eq!(
+            found_paths(3),
+            vec!["people.0.name", "people.1.name", "people.3.name"]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_fixed_strings() {
+        let json_value = json!({"versions": [{"x": "135"}, {"x": "1.5"}, {"x": 1.5}]});
+        let search_regex = Regex::new(&regex::escape("1.5")).unwrap();
+        let search_context = SearchContext::with_defaults(&search_regex);
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "x", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(found_paths, vec!["versions.1.x", "versions.2.x"]);
+    }
+
+    #[test]
+    fn test_search_json_value_equals() {
+        let json_value = json!({"configs": [
+            {"limits": {"cpu": 2, "tags": ["a", "b"]}},
+            {"limits": {"tags": ["a", "b"], "cpu": 2}},
+            {"limits": {"cpu": 2, "tags": ["b", "a"]}},
+            {"limits": {"cpu": 2.0, "tags": ["a", "b"]}},
+            {"limits": {"cpu": 2, "tags": ["a", "b"], "memory": 4}}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |field_name, expected_value: Value| {
+            let search_context = SearchContext {
+                equals: Some(&expected_value),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], field_name, Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        // Key order does not matter, but array order and number types do
+        assert_eq!(
+            found_paths("limits", json!({"cpu": 2, "tags": ["a", "b"]})),
+            vec!["configs.0.limits", "configs.1.limits"]
+        );
+        assert_eq!(
+            found_paths("tags", json!(["b", "a"])),
+            vec!["configs.2.limits.tags"]
+        );
+        assert!(found_paths("limits", json!({"cpu": 3})).is_empty());
+    }
+
+    #[test]
+    fn test_search_json_value_has_non_ascii() {
+        let json_value = json!({"names": [
+            {"name": "Anna"},
+            {"name": "Zoë"},
+            {"name": "Иван"},
+            {"name": "plain ascii ~!"},
+            {"name": 3}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let search_context = SearchContext {
+            has_non_ascii: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "name", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(found_paths, vec!["names.1.name", "names.2.name"]);
+    }
+
+    #[test]
+    fn test_search_json_value_value_filter() {
+        let json_value = json!({"people": [
+            {"age": 25},
+            {"age": 42},
+            {"age": "50"},
+            {"age": {"years": 50}},
+            {"age": 64}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let value_filter = ValueFilter::parse(". > 30 and . < 65").unwrap();
+        let search_context = SearchContext {
+            value_filter: Some(&value_filter),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "age", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(found_paths, vec!["people.1.age", "people.4.age"]);
+    }
+
+    #[test]
+    fn test_search_json_value_invert_match() {
+        let json_value = json!({"a": "apple", "b": {"a": "banana"}, "c": {"a": {"x": 1}}});
+        let search_regex = Regex::new("^\"a").unwrap();
+        let search_context = SearchContext {
+            invert_match: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let found_paths: Vec<String> =
+            search_json_value(&json_value, &[], "a", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect();
+        assert_eq!(found_paths, vec!["b.a"]);
+    }
+
+    #[test]
+    fn test_search_json_value_source_order() {
+        let json_value: Value = serde_json::from_str(
+            r#"{"zeta": {"id": 1}, "id": 2, "alpha": [{"id": 3}], "mid": {"id": 4, "b": {"id": 5}}}"#,
+        )
+        .unwrap();
+        let search_regex = Regex::new("").unwrap();
+        let found_values: Vec<Value> = search_json_value(
+            &json_value,
+            &[],
+            "id",
+            Vec::new(),
+            &SearchContext::with_defaults(&search_regex),
+        )
+        .unwrap_or_default()
+        .into_iter()
+        .map(|result| result.value)
+        .collect();
+        assert_eq!(
+            found_values,
+            vec![json!(1), json!(2), json!(3), json!(4), json!(5)]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_field_ignore_case() {
+        let json_value = json!({
+            "user": {"Name": "ada", "nested": {"NAME": "alan"}},
+            "User": {"name": "grace"}
+        });
+        let search_regex = Regex::new("a").unwrap();
+        let found_paths = |field_ignore_case| {
+            let search_context = SearchContext {
+                field_ignore_case,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &["user"], "name", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths(false), Vec::<String>::new());
+        // The path keeps the key's own case, and "User" does not match the path part "user"
+        assert_eq!(found_paths(true), vec!["user.Name", "user.nested.NAME"]);
+    }
+
+    #[test]
+    fn test_search_json_value_key_by() {
+        let json_value = json!({"users": [
+            {"id": "u1", "name": "ada"},
+            {"id": 2, "name": "alan"},
+            {"name": "anon"}
+        ]});
+        let search_regex = Regex::new("a").unwrap();
+        let search_context = SearchContext {
+            key_by: Some("id"),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let index_keys: Vec<Option<String>> =
+            search_json_value(&json_value, &[], "name", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.index_key)
+                .collect();
+        assert_eq!(
+            index_keys,
+            vec![Some("u1".to_string()), Some("2".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_path_filter() {
+        let json_value = json!({
+            "billing": {"address": {"city": "Berlin"}},
+            "shipping": {"address": {"city": "Berlin"}},
+            "items": ["Berlin"]
+        });
+        let search_regex = Regex::new("Berlin").unwrap();
+        let path_filter = Regex::new("bill").unwrap();
+        let search_context = SearchContext {
+            path_filter: Some(&path_filter),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "city", Vec::new(), &search_context);
+        assert_eq!(
+            results,
+            Some(vec![SearchResult {
+                json_path: vec![
+                    "billing".to_string(),
+                    "address".to_string(),
+                    "city".to_string()
+                ],
+                value: json!("Berlin"),
+                ..Default::default()
+            }]),
+        );
+
+        let path_filter = Regex::new(r"^items\.\d+$").unwrap();
+        let search_context = SearchContext {
+            path_filter: Some(&path_filter),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &["items"], "0", Vec::new(), &search_context);
+        assert_eq!(results.map(|results| results.len()), Some(1));
+    }
+
+    #[test]
+    fn test_search_json_value_path_regex() {
+        let json_value = json!({
+            "users": [{"status": "active"}, {"status": "inactive"}],
+            "groups": [{"status": "active"}],
+            "status": "active"
+        });
+        let search_regex = Regex::new("\"active\"").unwrap();
+        let found_paths = |path_regex: &str| {
+            let path_regex = Regex::new(path_regex).unwrap();
+            let search_context = SearchContext {
+                path_regex: Some(&path_regex),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "status", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths("users"), vec!["users.0.status"]);
+        assert_eq!(
+            found_paths(r"^\w+\.\d+\.status$"),
+            vec!["users.0.status", "groups.0.status"]
+        );
+        assert_eq!(found_paths("^status$"), vec!["status"]);
+        assert!(found_paths("admins").is_empty());
+    }
+
+    #[test]
+    fn test_search_json_value_not_types() {
+        let json_value = json!([{"a": "1"}, {"a": 2}, {"a": true}, {"a": null}, {"a": [3]}]);
+        let search_regex = Regex::new("").unwrap();
+        let found_values = |not_types| {
+            let search_context = SearchContext {
+                exists_only: true,
+                not_types,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "a", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_values(&[JsonType::String]),
+            vec![json!(2), json!(true), json!(null), json!([3])],
+        );
+        assert_eq!(
+            found_values(&[JsonType::String, JsonType::Null, JsonType::Array]),
+            vec![json!(2), json!(true)],
+        );
+
+        // The filter also applies in regex mode
+        let search_regex = Regex::new(".").unwrap();
+        let search_context = SearchContext {
+            not_types: &[JsonType::String],
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "a", Vec::new(), &search_context)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| result.value)
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![json!(2), json!(true)]);
+    }
+
+    #[test]
+    fn test_search_json_value_aggregate() {
+        let json_value = json!({"orders": [
+            {"a": 60, "b": 50},
+            {"a": 30, "b": 20},
+            {"a": 150, "b": "x"},
+            {"a": 120},
+            {"a": 101, "b": 0}
+        ]});
+        let search_regex = Regex::new("").unwrap();
+        let found_paths = |function, term| {
+            let search_context = SearchContext {
+                aggregate: Some(Aggregate {
+                    function,
+                    fields: vec!["a", "b"],
+                    term: NumericSearchTerm::from_search_term(term).unwrap(),
+                }),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "orders", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_paths(AggregateFunction::Sum, ">100"),
+            vec!["orders.0", "orders.4"],
+        );
+        assert_eq!(
+            found_paths(AggregateFunction::Max, ">=60"),
+            vec!["orders.0", "orders.4"],
+        );
+        assert_eq!(found_paths(AggregateFunction::Max, "<50"), vec!["orders.1"]);
+        assert_eq!(found_paths(AggregateFunction::Min, "==0"), vec!["orders.4"]);
+    }
+
+    #[test]
+    fn test_search_json_value_array_mode() {
+        let json_value = json!({"log": [
+            {"status": "ok", "id": 1},
+            {"status": "failed"},
+            {"status": "ok", "id": 2},
+            {"status": "ok", "id": 3}
+        ]});
+        let search_regex = Regex::new("ok").unwrap();
+        let found_paths = |array_mode| {
+            let search_context = SearchContext {
+                array_mode,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "status", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.json_path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_paths(ArrayMode::First), vec!["log.0.status"]);
+        assert_eq!(
+            found_paths(ArrayMode::All),
+            vec!["log.0.status", "log.2.status", "log.3.status"],
+        );
+        assert_eq!(found_paths(ArrayMode::Last), vec!["log.3.status"]);
+    }
+
+    #[test]
+    fn test_search_json_value_exact_term() {
+        let json_value = json!([
+            {"a": "cat"},
+            {"a": "category"},
+            {"a": "the cat"},
+            {"a": 1.5},
+            {"a": true},
+            {"a": ["cat"]}
+        ]);
+        let search_regex = Regex::new("").unwrap();
+        let found_values = |exact_term| {
+            let search_context = SearchContext {
+                exact_term: Some(exact_term),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "a", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found_values("cat"), vec![json!("cat")]);
+        assert_eq!(found_values("1.5"), vec![json!(1.5)]);
+        assert_eq!(found_values("true"), vec![json!(true)]);
+        assert_eq!(found_values("ca"), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn test_search_json_value_concat_search() {
+        let json_value = json!({
+            "records": [
+                {"first": "Ada", "last": "Lovelace", "age": 36},
+                {"first": "Alan", "nested": {"last": "Turing"}, "tags": ["Lovelace"]}
+            ]
+        });
+        let search_regex = Regex::new("Ada Lovelace").unwrap();
+        let search_context = SearchContext {
+            concat_search: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results =
+            search_json_value(&json_value, &["records"], "0", Vec::new(), &search_context);
+        assert_eq!(
+            results,
+            Some(vec![SearchResult {
+                json_path: vec!["records".to_string(), "0".to_string()],
+                value: json_value["records"][0].clone(),
+                ..Default::default()
+            }]),
+        );
+        let results =
+            search_json_value(&json_value, &["records"], "1", Vec::new(), &search_context);
+        assert_eq!(results, None);
+
+        let search_regex = Regex::new("^Alan Turing Lovelace$").unwrap();
+        let search_context = SearchContext {
+            concat_search: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "records", Vec::new(), &search_context);
+        assert_eq!(results, None);
+        let results =
+            search_json_value(&json_value, &["records"], "1", Vec::new(), &search_context);
+        assert_eq!(results.map(|results| results.len()), Some(1));
+    }
+
+    #[test]
+    fn test_search_json_value_numeric_relative_epsilon() {
+        let json_value = json!({"a": 1.0e9, "b": {"a": 1.0005e9}, "c": {"a": 1.002e9}});
+        let search_regex = Regex::new("==1e9").unwrap();
+        let search_context = SearchContext {
+            numeric_search_enabled: true,
+            rel_epsilon: 0.001,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "a", Vec::new(), &search_context)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| result.value)
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![json!(1.0e9), json!(1.0005e9)]);
+    }
+
+    #[test]
+    fn test_search_json_value_unicode_normalization() {
+        let json_value = json!({"a": "cafe\u{301}", "b": "caf\u{e9}"});
+        for normalization_form in [NormalizationForm::Nfc, NormalizationForm::Nfd] {
+            // The search term is normalized before compiling the regex
+            let search_regex = Regex::new(&normalization_form.normalize("caf\u{e9}")).unwrap();
+            let search_context = SearchContext {
+                normalization_form: Some(normalization_form),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            for field_name in ["a", "b"] {
+                let results =
+                    search_json_value(&json_value, &[], field_name, Vec::new(), &search_context);
+                assert_eq!(
+                    results,
+                    Some(vec![SearchResult {
+                        json_path: vec![field_name.to_string()],
+                        value: json_value[field_name].clone(),
+                        ..Default::default()
+                    }]),
+                );
+            }
+        }
+
+        let search_regex = Regex::new("caf\u{e9}").unwrap();
+        let search_context = SearchContext::with_defaults(&search_regex);
+        let results = search_json_value(&json_value, &[], "a", Vec::new(), &search_context);
+        assert_eq!(results, None);
+    }
+
+    #[test]
+    fn test_search_json_value_array_sample() {
+        let json_value = json!({"items": [{"a": 1}, {"a": 2}, {"a": 3}, [{"a": 4}]]});
+        let search_regex = Regex::new("\\d").unwrap();
+        let found_values = |array_sample| {
+            let search_context = SearchContext {
+                array_sample,
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            search_json_value(&json_value, &[], "a", Vec::new(), &search_context)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found_values(Some(ArraySample::Head(2))),
+            vec![json!(1), json!(2)]
+        );
+        assert_eq!(
+            found_values(Some(ArraySample::Tail(2))),
+            vec![json!(3), json!(4)]
+        );
+        assert_eq!(
+            found_values(Some(ArraySample::Head(10))),
+            vec![json!(1), json!(2), json!(3), json!(4)],
+        );
+        assert_eq!(
+            found_values(Some(ArraySample::Tail(0))),
+            Vec::<Value>::new()
+        );
+    }
+
+    #[test]
+    fn test_check_array_match_index_outside_sample() {
+        let json_value = json!({"items": ["x", "y", "z"]});
+        let search_regex = Regex::new(".").unwrap();
+        let search_context = SearchContext {
+            array_sample: Some(ArraySample::Head(1)),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &["items"], "0", Vec::new(), &search_context);
+        assert_eq!(results.map(|results| results.len()), Some(1));
+        let results = search_json_value(&json_value, &["items"], "2", Vec::new(), &search_context);
+        assert_eq!(results, None);
+    }
+
+    #[test]
+    fn test_search_json_value_parse_embedded() {
+        let json_value = json!({
+            "payload": "{\"id\": 5, \"inner\": \"[{\\\"id\\\": 6}]\"}",
+            "plain": "5",
+            "id": 7
+        });
+        let search_regex = Regex::new("\\d").unwrap();
+        let search_context = SearchContext {
+            parse_embedded: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "id", Vec::new(), &search_context);
+        assert_eq!(
+            results,
+            Some(vec![
+                SearchResult {
+                    json_path: vec!["payload~".to_string(), "id".to_string()],
+                    value: json!(5),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec![
+                        "payload~".to_string(),
+                        "inner~".to_string(),
+                        "0".to_string(),
+                        "id".to_string()
+                    ],
+                    value: json!(6),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["id".to_string()],
+                    value: json!(7),
+                    ..Default::default()
+                },
+            ]),
+        );
+
+        let results = search_json_value(
+            &json_value,
+            &["payload~"],
+            "id",
+            Vec::new(),
+            &search_context,
+        );
+        assert_eq!(results.map(|results| results.len()), Some(2));
+
+        let search_context = SearchContext::with_defaults(&search_regex);
+        let results = search_json_value(&json_value, &[], "id", Vec::new(), &search_context);
+        assert_eq!(results.map(|results| results.len()), Some(1));
+    }
+
+    #[test]
+    fn test_search_json_value_named_captures() {
+        let json_value = json!({"a": "alice-42", "b": "bob", "c": {"a": "carol-7"}});
+        let search_regex = Regex::new(r"(?P<user>\w+)-(?P<id>\d+)").unwrap();
+        let search_context = SearchContext {
+            captures: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(&json_value, &[], "a", Vec::new(), &search_context);
+        assert_eq!(
+            results,
+            Some(vec![
+                SearchResult {
+                    json_path: vec!["a".to_string()],
+                    value: json!({"user": "alice", "id": "42"}),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["c".to_string(), "a".to_string()],
+                    value: json!({"user": "carol", "id": "7"}),
+                    ..Default::default()
+                },
+            ]),
+        );
+        let results = search_json_value(&json_value, &[], "b", Vec::new(), &search_context);
+        assert_eq!(results, None);
+    }
+
+    #[test]
+    fn test_search_json_value_named_captures_whole_string() {
+        let json_value = json!({"name": "Max \"M\"\n", "age": 42});
+        let search_regex = Regex::new(r"^(?P<first>.)(?P<rest>(?s).*)$").unwrap();
+        let search_context = SearchContext {
+            captures: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let captured_value = |field_name| {
+            search_json_value(&json_value, &[], field_name, Vec::new(), &search_context).unwrap()[0]
+                .value
+                .clone()
+        };
+        // Neither the JSON quotes nor escape sequences are part of the captured text
+        assert_eq!(
+            captured_value("name"),
+            json!({"first": "M", "rest": "ax \"M\"\n"})
+        );
+        assert_eq!(captured_value("age"), json!({"first": "4", "rest": "2"}));
+    }
+
+    #[test]
+    fn test_named_captures_object_unmatched_group() {
+        let search_regex = Regex::new(r"(?P<user>\w+)(-(?P<id>\d+))?").unwrap();
+        let captures = search_regex.captures("alice").unwrap();
+        assert_eq!(
+            named_captures_object(&search_regex, &captures),
+            json!({"user": "alice", "id": null}),
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_exists_only() {
+        let json_value = json!({"a": {"b": null}, "c": {"b": {"d": 1}}, "e": {"f": 1}});
+        let field_path_parts = &[];
+        let search_regex = Regex::new("").unwrap();
+        let search_context = SearchContext {
+            exists_only: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            "b",
+            Vec::new(),
+            &search_context,
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    json_path: vec!["a".to_string(), "b".to_string()],
+                    value: json!(null),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["c".to_string(), "b".to_string()],
+                    value: json!({"d": 1}),
+                    ..Default::default()
+                },
+            ],
+        );
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            "missing",
+            Vec::new(),
+            &search_context,
+        );
+        assert_eq!(results, None);
+    }
+
+    #[test]
+    fn test_search_json_value_keys_and_values() {
+        let json_value = json!({
+            "config": {
+                "timeout": 30,
+                "retries": "timeout exceeded",
+                "nested": {"other": "value"}
+            },
+            "timeout": 10
+        });
+        let field_path_parts = &[];
+        let field_name = "config";
+        let search_regex = Regex::new("timeout").unwrap();
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                keys_and_values: true,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    json_path: vec!["config".to_string(), "timeout".to_string()],
+                    value: json!(30),
+                    matched_on: Some(MatchedOn::Key),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["config".to_string(), "retries".to_string()],
+                    value: json!("timeout exceeded"),
+                    matched_on: Some(MatchedOn::Value),
+                    ..Default::default()
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_array_index_field_name() {
+        let json_value = json!({"a": ["x", "test", {"1": "test"}]});
+        let field_path_parts = &["a"];
+        let field_name = "1";
+        let search_regex = Regex::new("test").unwrap();
+        let results = search_json_value(
             &json_value,
             field_path_parts,
             field_name,
             Vec::new(),
-            search_context,
-        ),
-        Err(e) => {
-            eprintln!("JSON parsing error: {}", e);
-            None
-        }
+            &SearchContext::with_defaults(&search_regex),
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    json_path: vec!["a".to_string(), "2".to_string(), "1".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["a".to_string(), "1".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+            ],
+        );
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use regex::Regex;
-    use serde_json::json;
 
     #[test]
-    fn test_search_json_value_single_match_object() {
+    fn test_search_json_value_max_array_depth() {
         let json_value = json!({
-            "a": {
-                "b": {
-                    "c": "test"
-                }
-            }
+            "a": {"b": {"c": {"name": "test"}}},
+            "list": [{"name": "test", "nested": [{"name": "test"}]}]
         });
-        let field_path_parts = &["a", "b"];
-        let field_name = "c";
+        let field_path_parts = &[];
+        let field_name = "name";
         let search_regex = Regex::new("test").unwrap();
         let results = search_json_value(
             &json_value,
@@ -243,105 +3182,163 @@ mod tests {
             field_name,
             Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: true,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
+                max_array_depth: Some(1),
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
         assert_eq!(
             results,
-            vec![SearchResult {
-                json_path: vec!["a".to_string(), "b".to_string(), "c".to_string()],
-                value: json!("test")
-            }],
+            vec![
+                SearchResult {
+                    json_path: vec![
+                        "a".to_string(),
+                        "b".to_string(),
+                        "c".to_string(),
+                        "name".to_string()
+                    ],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["list".to_string(), "0".to_string(), "name".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+            ],
         );
     }
 
     #[test]
-    fn test_search_json_value_single_match_array() {
-        let json_value = json!([
-            {"a": "test1"},
-            {"a": "test2"}
-        ]);
+    fn test_search_json_value_max_object_depth() {
+        let json_value = json!({
+            "name": "test",
+            "a": {"name": "test", "b": {"name": "test"}},
+            "list": [[["test"]]]
+        });
         let field_path_parts = &[];
-        let field_name = "a";
-        let search_regex = Regex::new("test2").unwrap();
+        let field_name = "0";
+        let search_regex = Regex::new("test").unwrap();
+        let search_context = SearchContext {
+            max_object_depth: Some(2),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            "name",
+            Vec::new(),
+            &search_context,
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    json_path: vec!["name".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["a".to_string(), "name".to_string()],
+                    value: json!("test"),
+                    ..Default::default()
+                },
+            ],
+        );
         let results = search_json_value(
             &json_value,
             field_path_parts,
             field_name,
             Vec::new(),
-            &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: true,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
-            },
+            &search_context,
         )
         .unwrap_or_default();
         assert_eq!(
             results,
             vec![SearchResult {
-                json_path: vec!["1".to_string(), "a".to_string()],
-                value: json!("test2"),
+                json_path: vec![
+                    "list".to_string(),
+                    "0".to_string(),
+                    "0".to_string(),
+                    "0".to_string()
+                ],
+                value: json!("test"),
+                ..Default::default()
             }],
         );
     }
 
     #[test]
-    fn test_search_json_value_multiple_matches_object() {
+    fn test_search_json_value_deadline_exceeded() {
+        let json_value = Value::Array(vec![json!({"a": {"b": "test"}}); 100_000]);
+        let field_path_parts = &[];
+        let field_name = "b";
+        let search_regex = Regex::new("test").unwrap();
+        let search_context = SearchContext {
+            deadline: Some(Instant::now()),
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &search_context,
+        );
+        assert!(search_context.deadline_exceeded());
+        assert_eq!(results, None);
+    }
+
+    #[test]
+    fn test_search_json_value_exclude_path() {
         let json_value = json!({
-            "a": {
-                "b": "test",
-                "c": "test"
-            }
+            "debug": {"a": "test", "nested": {"a": "test"}},
+            "data": {"a": "test"}
         });
-        let field_path_parts = &["a"];
-        let field_name = "b";
+        let field_path_parts = &[];
+        let field_name = "a";
         let search_regex = Regex::new("test").unwrap();
+        let exclude_paths = vec![vec!["debug"]];
         let results = search_json_value(
             &json_value,
             field_path_parts,
             field_name,
             Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: false,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
+                exclude_paths: &exclude_paths,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
         assert_eq!(
             results,
             vec![SearchResult {
-                json_path: vec!["a".to_string(), "b".to_string()],
+                json_path: vec!["data".to_string(), "a".to_string()],
                 value: json!("test"),
+                ..Default::default()
             }],
         );
     }
 
     #[test]
-    fn test_search_json_value_multiple_matches_array() {
-        let json_value = json!([
-            {"a": "test"},
-            {"a": "test"}
-        ]);
+    fn test_search_json_value_multiple_exclude_paths() {
+        let json_value = json!({
+            "list": [{"a": "test"}, {"a": "test"}, {"a": "test"}],
+            "b": {"a": "test"}
+        });
         let field_path_parts = &[];
         let field_name = "a";
         let search_regex = Regex::new("test").unwrap();
+        let exclude_paths = [vec!["list", "1"], vec!["b", "a"], vec!["list", "*", "a"]];
         let results = search_json_value(
             &json_value,
             field_path_parts,
             field_name,
             Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: false,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
+                exclude_paths: &exclude_paths[..2],
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -349,33 +3346,25 @@ mod tests {
             results,
             vec![
                 SearchResult {
-                    json_path: vec!["0".to_string(), "a".to_string()],
+                    json_path: vec!["list".to_string(), "0".to_string(), "a".to_string()],
                     value: json!("test"),
+                    ..Default::default()
                 },
                 SearchResult {
-                    json_path: vec!["1".to_string(), "a".to_string()],
+                    json_path: vec!["list".to_string(), "2".to_string(), "a".to_string()],
                     value: json!("test"),
+                    ..Default::default()
                 },
             ],
         );
-    }
-
-    #[test]
-    fn test_search_json_value_no_match() {
-        let json_value = json!({"a": "value"});
-        let field_path_parts = &[];
-        let field_name = "b";
-        let search_regex = Regex::new("test").unwrap();
         let results = search_json_value(
             &json_value,
             field_path_parts,
             field_name,
             Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: false,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
+                exclude_paths: &exclude_paths[1..],
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -383,11 +3372,69 @@ mod tests {
     }
 
     #[test]
-    fn test_search_json_value_field_path_match() {
-        let json_value = json!({"a":{"b":{"c":"test"}}});
-        let field_path_parts = &["a", "b"];
-        let field_name = "c";
-        let search_regex = Regex::new("test").unwrap();
+    fn test_process_json_input_annotate_types() {
+        let json_input = r#"{"users": [{"name": "Max"}]}"#.to_string();
+        let field_path_parts = &["users"];
+        let field_name = "name";
+        let search_regex = Regex::new("Max").unwrap();
+        let results = process_json_input(
+            json_input,
+            field_path_parts,
+            field_name,
+            &SearchContext {
+                annotate_types: true,
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap()
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["users".to_string(), "0".to_string(), "name".to_string()],
+                value: json!("Max"),
+                path_types: vec!["array", "object", "string"],
+                ..Default::default()
+            }],
+        );
+    }
+
+    #[test]
+    fn test_process_json_input_context() {
+        let json_input = r#"{"users": [{"id": 1, "name": "Max"}, {"id": 2, "name": "Eve"}]}"#;
+        let search_regex = Regex::new("Max").unwrap();
+        let contexts = |context_depth| {
+            let search_context = SearchContext {
+                context_depth: Some(context_depth),
+                ..SearchContext::with_defaults(&search_regex)
+            };
+            process_json_input(json_input.to_string(), &[], "name", &search_context)
+                .unwrap()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|result| result.context)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contexts(1), vec![Some(json!({"id": 1, "name": "Max"}))]);
+        assert_eq!(
+            contexts(2),
+            vec![Some(
+                json!([{"id": 1, "name": "Max"}, {"id": 2, "name": "Eve"}])
+            )]
+        );
+        // Deeper levels stop at the root
+        assert_eq!(
+            contexts(5),
+            vec![Some(serde_json::from_str::<Value>(json_input).unwrap())]
+        );
+    }
+
+    #[test]
+    fn test_search_json_value_numeric_greater_than() {
+        let json_value = json!({"a": 30});
+        let field_path_parts = &[];
+        let field_name = "a";
+        let search_regex = Regex::new(">25").unwrap();
         let results = search_json_value(
             &json_value,
             field_path_parts,
@@ -397,70 +3444,110 @@ mod tests {
                 search_regex: &search_regex,
                 single_result_only: false,
                 field_path_separator: ".",
-                numeric_search_enabled: false,
+                numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
         assert_eq!(
             results,
             vec![SearchResult {
-                json_path: vec!["a".to_string(), "b".to_string(), "c".to_string()],
-                value: json!("test"),
+                json_path: vec!["a".to_string()],
+                value: json!(30),
+                ..Default::default()
             }],
         );
     }
 
     #[test]
-    fn test_process_json_input_valid() {
-        let json_input = r#"{"a": "test"}"#.to_string();
+    fn test_search_json_value_numeric_string_without_coercion() {
+        let json_value = json!({"a": "30"});
         let field_path_parts = &[];
         let field_name = "a";
-        let search_regex = Regex::new("test").unwrap();
-        let results = process_json_input(
-            json_input,
+        let search_regex = Regex::new(">25").unwrap();
+        let results = search_json_value(
+            &json_value,
             field_path_parts,
             field_name,
+            Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: false,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
+                numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
+        assert_eq!(results, vec![]);
+    }
+
+    #[test]
+    fn test_search_json_value_numeric_string_with_coercion() {
+        let json_value = json!({"a": "30", "b": "abc"});
+        let field_path_parts = &[];
+        let search_regex = Regex::new(">25").unwrap();
+        let search_context = SearchContext {
+            numeric_search_enabled: true,
+            coerce_strings: true,
+            ..SearchContext::with_defaults(&search_regex)
+        };
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            "a",
+            Vec::new(),
+            &search_context,
+        )
+        .unwrap_or_default();
         assert_eq!(
             results,
             vec![SearchResult {
                 json_path: vec!["a".to_string()],
-                value: json!("test"),
+                value: json!("30"),
+                ..Default::default()
             }],
         );
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            "b",
+            Vec::new(),
+            &search_context,
+        )
+        .unwrap_or_default();
+        assert_eq!(results, vec![]);
     }
 
     #[test]
-    fn test_process_json_input_invalid() {
-        let json_input = r#"{invalid json"#.to_string();
+    fn test_search_json_value_numeric_additional_terms() {
+        let json_value = json!({"a": [{"age": 30}, {"age": 70}, {"age": 10}]});
         let field_path_parts = &[];
-        let field_name = "a";
-        let search_regex = Regex::new("test").unwrap();
-        let results = process_json_input(
-            json_input,
+        let field_name = "age";
+        let search_regex = Regex::new(">18").unwrap();
+        let additional_numeric_terms = [NumericSearchTerm::from_search_term("<65").unwrap()];
+        let results = search_json_value(
+            &json_value,
             field_path_parts,
             field_name,
+            Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: false,
-                field_path_separator: ".",
-                numeric_search_enabled: false,
+                numeric_search_enabled: true,
+                additional_numeric_terms: &additional_numeric_terms,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
-        assert_eq!(results, vec![]);
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                json_path: vec!["a".to_string(), "0".to_string(), "age".to_string()],
+                value: json!(30),
+                ..Default::default()
+            }],
+        );
     }
 
     #[test]
-    fn test_search_json_value_numeric_greater_than() {
-        let json_value = json!({"a": 30});
+    fn test_search_json_value_number_kind() {
+        let json_value = json!({"list": [{"a": 30}, {"a": 30.5}, {"a": 30.0}, {"a": "30"}]});
         let field_path_parts = &[];
         let field_name = "a";
         let search_regex = Regex::new(">25").unwrap();
@@ -470,20 +3557,48 @@ mod tests {
             field_name,
             Vec::new(),
             &SearchContext {
-                search_regex: &search_regex,
-                single_result_only: false,
-                field_path_separator: ".",
                 numeric_search_enabled: true,
+                coerce_strings: true,
+                number_kind: Some(NumberKind::Int),
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
         assert_eq!(
             results,
             vec![SearchResult {
-                json_path: vec!["a".to_string()],
+                json_path: vec!["list".to_string(), "0".to_string(), "a".to_string()],
                 value: json!(30),
+                ..Default::default()
             }],
         );
+        let results = search_json_value(
+            &json_value,
+            field_path_parts,
+            field_name,
+            Vec::new(),
+            &SearchContext {
+                numeric_search_enabled: true,
+                number_kind: Some(NumberKind::Float),
+                ..SearchContext::with_defaults(&search_regex)
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    json_path: vec!["list".to_string(), "1".to_string(), "a".to_string()],
+                    value: json!(30.5),
+                    ..Default::default()
+                },
+                SearchResult {
+                    json_path: vec!["list".to_string(), "2".to_string(), "a".to_string()],
+                    value: json!(30.0),
+                    ..Default::default()
+                },
+            ],
+        );
     }
 
     #[test]
@@ -502,6 +3617,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -510,6 +3626,7 @@ mod tests {
             vec![SearchResult {
                 json_path: vec!["a".to_string()],
                 value: json!(10),
+                ..Default::default()
             }],
         );
     }
@@ -530,6 +3647,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -552,6 +3670,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -574,6 +3693,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -582,6 +3702,7 @@ mod tests {
             vec![SearchResult {
                 json_path: vec!["a".to_string()],
                 value: json!(15),
+                ..Default::default()
             }],
         );
     }
@@ -602,6 +3723,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -624,6 +3746,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -646,6 +3769,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -654,6 +3778,7 @@ mod tests {
             vec![SearchResult {
                 json_path: vec!["a".to_string()],
                 value: json!(10),
+                ..Default::default()
             }],
         );
     }
@@ -674,6 +3799,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -696,6 +3822,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -718,6 +3845,7 @@ mod tests {
                 single_result_only: false,
                 field_path_separator: ".",
                 numeric_search_enabled: true,
+                ..SearchContext::with_defaults(&search_regex)
             },
         )
         .unwrap_or_default();
@@ -726,6 +3854,7 @@ mod tests {
             vec![SearchResult {
                 json_path: vec!["a".to_string()],
                 value: json!(12),
+                ..Default::default()
             }],
         );
     }
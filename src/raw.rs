@@ -0,0 +1,158 @@
+/// Returns the source text of the value at the path in the JSON input, e.g. `2.50` where
+/// serde_json would print `2.5`. None if the path does not exist or the input is invalid.
+pub fn raw_token<'a>(json_input: &'a str, json_path: &[impl AsRef<str>]) -> Option<&'a str> {
+    let start = skip_whitespace(json_input.as_bytes(), 0);
+    let (start, end) = find_value(json_input, start, json_path)?;
+    Some(&json_input[start..end])
+}
+
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Returns the start and end of the value at the path below the value starting at `start`.
+fn find_value(
+    json_input: &str,
+    start: usize,
+    json_path: &[impl AsRef<str>],
+) -> Option<(usize, usize)> {
+    let bytes = json_input.as_bytes();
+    let Some((path_part, rest_path)) = json_path.split_first() else {
+        return Some((start, value_end(bytes, start)?));
+    };
+    match bytes.get(start)? {
+        b'{' => {
+            let mut found = None;
+            let mut pos = skip_whitespace(bytes, start + 1);
+            if bytes.get(pos) == Some(&b'}') {
+                return None;
+            }
+            loop {
+                let key_end = value_end(bytes, pos)?;
+                let key: String = serde_json::from_str(&json_input[pos..key_end]).ok()?;
+                pos = skip_whitespace(bytes, key_end);
+                if bytes.get(pos) != Some(&b':') {
+                    return None;
+                }
+                let value_start = skip_whitespace(bytes, pos + 1);
+                if key == path_part.as_ref() {
+                    // Like serde_json, the last of duplicate keys wins
+                    found = find_value(json_input, value_start, rest_path);
+                }
+                pos = skip_whitespace(bytes, value_end(bytes, value_start)?);
+                match bytes.get(pos)? {
+                    b',' => pos = skip_whitespace(bytes, pos + 1),
+                    b'}' => return found,
+                    _ => return None,
+                }
+            }
+        }
+        b'[' => {
+            let index: usize = path_part.as_ref().parse().ok()?;
+            let mut pos = skip_whitespace(bytes, start + 1);
+            if bytes.get(pos) == Some(&b']') {
+                return None;
+            }
+            for _ in 0..index {
+                pos = skip_whitespace(bytes, value_end(bytes, pos)?);
+                if bytes.get(pos) != Some(&b',') {
+                    return None;
+                }
+                pos = skip_whitespace(bytes, pos + 1);
+            }
+            find_value(json_input, pos, rest_path)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the position after the value starting at `start`. Structural characters are ASCII,
+/// so scanning bytes never splits a multibyte character.
+fn value_end(bytes: &[u8], start: usize) -> Option<usize> {
+    match bytes.get(start)? {
+        b'"' => {
+            let mut pos = start + 1;
+            loop {
+                match bytes.get(pos)? {
+                    b'\\' => pos += 2,
+                    b'"' => return Some(pos + 1),
+                    _ => pos += 1,
+                }
+            }
+        }
+        b'{' | b'[' => {
+            let mut depth = 0;
+            let mut pos = start;
+            loop {
+                match bytes.get(pos)? {
+                    b'"' => {
+                        pos = value_end(bytes, pos)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(pos + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                pos += 1;
+            }
+        }
+        _ => {
+            let len = bytes[start..]
+                .iter()
+                .position(|byte| matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace())
+                .unwrap_or(bytes.len() - start);
+            (len > 0).then_some(start + len)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_token_numbers_as_written() {
+        let json_input =
+            r#"{"price": 2.50, "pi": 3.14159265358979323846264338327950288, "big": 1E+3}"#;
+        assert_eq!(raw_token(json_input, &["price"]), Some("2.50"));
+        assert_eq!(
+            raw_token(json_input, &["pi"]),
+            Some("3.14159265358979323846264338327950288")
+        );
+        assert_eq!(raw_token(json_input, &["big"]), Some("1E+3"));
+    }
+
+    #[test]
+    fn test_raw_token_nested() {
+        let json_input = r#"
+            {
+                "a": {"b,}": "x \"}]", "c": [ 1.0 , {"d": -0.000}, "é"]},
+                "e": [[], {}]
+            }
+        "#;
+        assert_eq!(raw_token(json_input, &["a", "b,}"]), Some(r#""x \"}]""#));
+        assert_eq!(raw_token(json_input, &["a", "c", "0"]), Some("1.0"));
+        assert_eq!(raw_token(json_input, &["a", "c", "1", "d"]), Some("-0.000"));
+        assert_eq!(raw_token(json_input, &["a", "c", "2"]), Some(r#""é""#));
+        assert_eq!(raw_token(json_input, &["e", "1"]), Some("{}"));
+        assert_eq!(raw_token(json_input, &["a", "c", "3"]), None);
+        assert_eq!(raw_token(json_input, &["e", "0", "0"]), None);
+        assert_eq!(raw_token(json_input, &["missing"]), None);
+        assert_eq!(raw_token("7.10", &[] as &[&str]), Some("7.10"));
+    }
+
+    #[test]
+    fn test_raw_token_escaped_and_duplicate_keys() {
+        let json_input = r#"{"kéy": 1.50, "dup": 1.0, "dup": 2.00}"#;
+        assert_eq!(raw_token(json_input, &["kéy"]), Some("1.50"));
+        assert_eq!(raw_token(json_input, &["dup"]), Some("2.00"));
+    }
+}
@@ -1,3 +1,10 @@
+use std::fmt;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use clap::ValueEnum;
+use unicode_normalization::UnicodeNormalization;
+
 pub fn parse_search_path<'a>(
     search_path: &'a str,
     field_path_separator: &'a str,
@@ -20,6 +27,173 @@ pub fn parse_search_path<'a>(
     }
 }
 
+/// Takes the field path and name from --in-path and --field if given, so that the field name
+/// may contain the separator. Otherwise parses the positional search path.
+pub fn resolve_search_path<'a>(
+    search_path: &'a str,
+    in_path: Option<&'a str>,
+    field: Option<&'a str>,
+    field_path_separator: &'a str,
+) -> Result<(Vec<&'a str>, &'a str), String> {
+    match field {
+        Some("") => Err("Invalid field format. Field name is empty.".to_string()),
+        Some(field_name) => Ok((
+            parse_path_parts(in_path.unwrap_or_default(), field_path_separator),
+            field_name,
+        )),
+        None => parse_search_path(search_path, field_path_separator),
+    }
+}
+
+pub fn parse_path_parts<'a>(path: &'a str, field_path_separator: &'a str) -> Vec<&'a str> {
+    if path.is_empty() {
+        Vec::new()
+    } else {
+        path.split(field_path_separator).collect()
+    }
+}
+
+/// Prefix of a search term that enables numeric search for it, e.g. `num:>30`.
+const NUMERIC_TERM_PREFIX: &str = "num:";
+
+/// Splits off the `num:` prefix, returning the term and whether it had the prefix.
+pub fn split_numeric_prefix(search_term: &str) -> (&str, bool) {
+    match search_term.strip_prefix(NUMERIC_TERM_PREFIX) {
+        Some(numeric_term) => (numeric_term, true),
+        None => (search_term, false),
+    }
+}
+
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₹', '₩', '₽', '¢'];
+
+/// Parses numbers formatted for humans, e.g. `$1,200.50` or `45%` (0.45). Currency symbols and
+/// the thousands separators `,`, `'`, `_` and spaces are dropped. A leading sign may come before
+/// or after the currency symbol.
+pub fn parse_formatted_number(formatted: &str) -> Option<f64> {
+    let formatted = formatted.trim();
+    let (formatted, divisor) = match formatted.strip_suffix('%') {
+        Some(percentage) => (percentage, 100.0),
+        None => (formatted, 1.0),
+    };
+    let digits: String = formatted
+        .chars()
+        .filter(|c| {
+            !(CURRENCY_SYMBOLS.contains(c) || matches!(c, ',' | '\'' | '_') || c.is_whitespace())
+        })
+        .collect();
+    // Rejects leftovers like "1e3", "inf" or "NaN", which f64 parsing would accept
+    if !digits
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+    {
+        return None;
+    }
+    digits.parse::<f64>().ok().map(|number| number / divisor)
+}
+
+/// Converts a shell glob into a regex matching the whole value. `*` matches any sequence of
+/// characters and `?` a single character. Values are matched in their JSON representation,
+/// so the quotes around strings are allowed as well.
+pub fn glob_to_regex(glob: &str) -> String {
+    let pattern: String = glob
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            _ => regex::escape(&c.to_string()),
+        })
+        .collect();
+    format!(r#"^(?:"{0}"|{0})$"#, pattern)
+}
+
+/// Anchors the regex to match the whole value for --whole-value, e.g. `test` no longer matches
+/// "mytestvalue". Like with globs, the quotes around strings are allowed. Anchors already
+/// enclosing the pattern are redundant and dropped, so `^test$` still matches "test".
+pub fn whole_value_regex(pattern: &str) -> String {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    // A trailing `\$` is a literal dollar sign rather than an anchor
+    let trailing_backslashes = pattern
+        .strip_suffix('$')
+        .map(|rest| rest.len() - rest.trim_end_matches('\\').len());
+    let pattern = match trailing_backslashes {
+        Some(count) if count % 2 == 0 => &pattern[..pattern.len() - 1],
+        _ => pattern,
+    };
+    format!(r#"^(?:"(?:{0})"|(?:{0}))$"#, pattern)
+}
+
+/// Unicode normalization form applied to string values and the search term before matching.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+}
+
+impl NormalizationForm {
+    pub fn normalize(&self, text: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => text.nfc().collect(),
+            NormalizationForm::Nfd => text.nfd().collect(),
+        }
+    }
+}
+
+/// Combines several numeric fields of an object into one number for `--aggregate`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum AggregateFunction {
+    Sum,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    pub fn apply(&self, numbers: &[f64]) -> f64 {
+        match self {
+            AggregateFunction::Sum => numbers.iter().sum(),
+            AggregateFunction::Min => numbers.iter().copied().fold(f64::INFINITY, f64::min),
+            AggregateFunction::Max => numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum JsonType {
+    String,
+    Number,
+    Boolean,
+    Null,
+    Object,
+    Array,
+}
+
+impl JsonType {
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            JsonType::String => value.is_string(),
+            JsonType::Number => value.is_number(),
+            JsonType::Boolean => value.is_boolean(),
+            JsonType::Null => value.is_null(),
+            JsonType::Object => value.is_object(),
+            JsonType::Array => value.is_array(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum NumberKind {
+    Int,
+    Float,
+}
+
+impl NumberKind {
+    pub fn matches(&self, number: &serde_json::Number) -> bool {
+        match self {
+            NumberKind::Int => number.is_i64() || number.is_u64(),
+            NumberKind::Float => number.is_f64(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ComparisonOperator {
     LessThan,
@@ -30,7 +204,7 @@ pub enum ComparisonOperator {
 }
 
 impl ComparisonOperator {
-    fn from_str(op_str: &str) -> Option<Self> {
+    pub fn from_str(op_str: &str) -> Option<Self> {
         match op_str {
             "<" => Some(ComparisonOperator::LessThan),
             "<=" => Some(ComparisonOperator::LessThanOrEqual),
@@ -40,6 +214,270 @@ impl ComparisonOperator {
             _ => None,
         }
     }
+
+    /// The operator as written in a search term, e.g. `>=`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ComparisonOperator::LessThan => "<",
+            ComparisonOperator::LessThanOrEqual => "<=",
+            ComparisonOperator::GreaterThan => ">",
+            ComparisonOperator::GreaterThanOrEqual => ">=",
+            ComparisonOperator::Equal => "==",
+        }
+    }
+
+    /// Whether `value` relates to `target` as given by the operator.
+    pub fn compare<T: PartialOrd>(&self, value: &T, target: &T) -> bool {
+        match self {
+            ComparisonOperator::LessThan => value < target,
+            ComparisonOperator::LessThanOrEqual => value <= target,
+            ComparisonOperator::GreaterThan => value > target,
+            ComparisonOperator::GreaterThanOrEqual => value >= target,
+            ComparisonOperator::Equal => value == target,
+        }
+    }
+}
+
+/// Describes how the search path and term were parsed, one 'name: value' line each, for --explain.
+/// The numeric term is only shown if numeric search is enabled, in the syntax of a search term.
+pub fn explain_search(
+    field_path_parts: &[&str],
+    field_name: &str,
+    search_regex: &str,
+    numeric_search: bool,
+) -> Vec<String> {
+    let numeric_term = NumericSearchTerm::from_search_term(search_regex)
+        .filter(|_| numeric_search)
+        .map_or_else(
+            || "none".to_string(),
+            |numeric_term| numeric_term.to_string(),
+        );
+    vec![
+        format!("field_path_parts: {:?}", field_path_parts),
+        format!("field_name: {:?}", field_name),
+        format!("numeric_term: {}", numeric_term),
+        format!("regex: {:?}", search_regex),
+    ]
+}
+
+/// Matches integers whose remainder modulo the divisor is fixed, for --divisible-by, --odd and
+/// --even.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Divisibility {
+    divisor: i128,
+    remainder: i128,
+}
+
+impl Divisibility {
+    pub fn divisible_by(divisor: u64) -> Self {
+        Divisibility {
+            divisor: divisor.into(),
+            remainder: 0,
+        }
+    }
+
+    pub fn odd() -> Self {
+        Divisibility {
+            divisor: 2,
+            remainder: 1,
+        }
+    }
+
+    pub fn even() -> Self {
+        Self::divisible_by(2)
+    }
+
+    /// Non-integers never match, including floats with an integral value such as `4.0`.
+    pub fn matches(&self, number: &serde_json::Number) -> bool {
+        as_integer(number).is_some_and(|integer| integer.rem_euclid(self.divisor) == self.remainder)
+    }
+}
+
+/// Reads a number stored as an integer. Floats yield None, even with an integral value.
+fn as_integer(number: &serde_json::Number) -> Option<i128> {
+    match (number.as_i64(), number.as_u64()) {
+        (Some(integer), _) => Some(i128::from(integer)),
+        (None, Some(integer)) => Some(i128::from(integer)),
+        (None, None) => None,
+    }
+}
+
+/// A Rust-style range of integers for --int-range, e.g. `1..100` excluding or `1..=100`
+/// including the end. Either bound may be omitted, as in `10..`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct IntRange {
+    start: Option<i128>,
+    end: Option<i128>,
+    inclusive: bool,
+}
+
+impl IntRange {
+    pub fn from_range(range: &str) -> Result<Self, String> {
+        let (start, end, inclusive) = match range.split_once("..=") {
+            Some((start, end)) => (start, end, true),
+            None => match range.split_once("..") {
+                Some((start, end)) => (start, end, false),
+                None => return Err("expected a range like '1..100' or '1..=100'".to_string()),
+            },
+        };
+        let parse_bound = |bound: &str| match bound.trim() {
+            "" => Ok(None),
+            bound => bound
+                .parse::<i128>()
+                .map(Some)
+                .map_err(|_| format!("invalid integer bound '{}'", bound)),
+        };
+        let int_range = IntRange {
+            start: parse_bound(start)?,
+            end: parse_bound(end)?,
+            inclusive,
+        };
+        if inclusive && int_range.end.is_none() {
+            return Err("an inclusive range needs an end".to_string());
+        }
+        Ok(int_range)
+    }
+
+    /// Non-integers never match, including floats with an integral value such as `50.0`.
+    pub fn matches(&self, number: &serde_json::Number) -> bool {
+        let Some(integer) = as_integer(number) else {
+            return false;
+        };
+        self.start.is_none_or(|start| integer >= start)
+            && self.end.is_none_or(|end| {
+                if self.inclusive {
+                    integer <= end
+                } else {
+                    integer < end
+                }
+            })
+    }
+}
+
+/// The 1-based, inclusive range of lines for --line-range, e.g. `120:180`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    pub fn from_range(range: &str) -> Result<Self, String> {
+        let (start, end) = range
+            .split_once(':')
+            .ok_or_else(|| "expected a range like '120:180'".to_string())?;
+        let parse_line = |line: &str| {
+            line.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|line_number| *line_number >= 1)
+                .ok_or_else(|| format!("invalid line number '{}', lines start at 1", line))
+        };
+        let line_range = LineRange {
+            start: parse_line(start)?,
+            end: parse_line(end)?,
+        };
+        if line_range.end < line_range.start {
+            return Err("the end is before the start".to_string());
+        }
+        Ok(line_range)
+    }
+}
+
+/// A comparison against a semantic version, e.g. `>=1.2.0`. Without an operator, the versions
+/// must be equal.
+#[derive(Debug, PartialEq)]
+pub struct VersionSearchTerm {
+    operator: ComparisonOperator,
+    version: semver::Version,
+}
+
+/// Splits a leading comparison operator off the search term, defaulting to equality.
+fn split_operator(search_term: &str) -> (ComparisonOperator, &str) {
+    let ops = ["<=", ">=", "<", ">", "=="];
+    ops.iter()
+        .find_map(|op_str| {
+            let operand = search_term.strip_prefix(op_str)?;
+            Some((ComparisonOperator::from_str(op_str)?, operand))
+        })
+        .unwrap_or((ComparisonOperator::Equal, search_term))
+}
+
+impl VersionSearchTerm {
+    pub fn from_search_term(search_term: &str) -> Result<Self, semver::Error> {
+        let (operator, version_str) = split_operator(search_term);
+        Ok(VersionSearchTerm {
+            operator,
+            version: semver::Version::parse(version_str.trim())?,
+        })
+    }
+
+    /// Invalid versions never match.
+    pub fn matches(&self, version_str: &str) -> bool {
+        semver::Version::parse(version_str)
+            .is_ok_and(|version| self.operator.compare(&version, &self.version))
+    }
+}
+
+/// Parses a date for --since and --until, either an RFC 3339 timestamp like
+/// `2024-05-01T12:00:00Z` or a day like `2024-05-01`, which starts at local midnight.
+pub fn parse_date(date: &str) -> Result<SystemTime, String> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(date) {
+        return Ok(date_time.into());
+    }
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+        "expected a day like '2024-05-01' or a timestamp like '2024-05-01T12:00:00Z'".to_string()
+    })?;
+    day.and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(|| "midnight does not exist in the local time zone".to_string())
+}
+
+/// A comparison of Unix timestamps against a date for --epoch and --epoch-ms, e.g.
+/// `>=2024-01-01`. Without an operator, the timestamp must be exactly the date.
+#[derive(Debug, PartialEq)]
+pub struct EpochSearchTerm {
+    operator: ComparisonOperator,
+    /// The date in milliseconds since the Unix epoch.
+    date_millis: f64,
+    /// Milliseconds per unit of the matched timestamps, 1000 for seconds.
+    unit_millis: f64,
+}
+
+impl EpochSearchTerm {
+    pub fn from_search_term(search_term: &str, milliseconds: bool) -> Result<Self, String> {
+        let (operator, date) = split_operator(search_term);
+        let date_time: DateTime<Utc> = parse_date(date.trim())?.into();
+        Ok(EpochSearchTerm {
+            operator,
+            date_millis: date_time.timestamp_millis() as f64,
+            unit_millis: if milliseconds { 1.0 } else { 1000.0 },
+        })
+    }
+
+    /// Fractional timestamps are compared as they are, e.g. `1.5` seconds.
+    pub fn matches(&self, number: &serde_json::Number) -> bool {
+        number.as_f64().is_some_and(|timestamp| {
+            self.operator
+                .compare(&(timestamp * self.unit_millis), &self.date_millis)
+        })
+    }
+}
+
+/// Parses a number with an optional case-insensitive `k`, `m` or `g` multiplier, e.g. `10k`.
+fn parse_operand(num_str: &str) -> Option<f64> {
+    let multipliers = [('k', 1e3), ('m', 1e6), ('g', 1e9)];
+    for (suffix, multiplier) in multipliers {
+        if let Some(num_str) = num_str
+            .strip_suffix(suffix)
+            .or_else(|| num_str.strip_suffix(suffix.to_ascii_uppercase()))
+        {
+            return num_str.parse::<f64>().ok().map(|num| num * multiplier);
+        }
+    }
+    num_str.parse::<f64>().ok()
 }
 
 #[derive(Debug, PartialEq)]
@@ -48,6 +486,25 @@ pub enum NumericSearchTerm {
     RangeComparison(ComparisonOperator, f64, ComparisonOperator, f64),
 }
 
+/// Renders the term as it would be written, with multipliers expanded, e.g. `>=1<10000`.
+impl fmt::Display for NumericSearchTerm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumericSearchTerm::SingleComparison(operator, value) => {
+                write!(f, "{}{}", operator.symbol(), value)
+            }
+            NumericSearchTerm::RangeComparison(operator1, value1, operator2, value2) => write!(
+                f,
+                "{}{}{}{}",
+                operator1.symbol(),
+                value1,
+                operator2.symbol(),
+                value2
+            ),
+        }
+    }
+}
+
 impl NumericSearchTerm {
     pub fn from_search_term(search_term: &str) -> Option<Self> {
         // Try to parse as range first
@@ -67,7 +524,7 @@ impl NumericSearchTerm {
         let ops = ["<=", ">=", "<", ">", "=="];
         for op_str in ops {
             if let Some(num_str) = search_term.strip_prefix(op_str) {
-                if let Ok(num_value) = num_str.parse::<f64>() {
+                if let Some(num_value) = parse_operand(num_str) {
                     if let Some(operator) = ComparisonOperator::from_str(op_str) {
                         return Some(NumericSearchTerm::SingleComparison(operator, num_value));
                     }
@@ -87,8 +544,8 @@ impl NumericSearchTerm {
                         let rest2 = &rest1[num_str1_end_op2..];
                         let num_str2 = &rest2[op2_str.len()..];
 
-                        if let (Ok(num1), Ok(num2)) =
-                            (num_str1.parse::<f64>(), num_str2.parse::<f64>())
+                        if let (Some(num1), Some(num2)) =
+                            (parse_operand(num_str1), parse_operand(num_str2))
                         {
                             if let (Some(op1), Some(op2)) = (
                                 ComparisonOperator::from_str(op1_str),
@@ -106,34 +563,43 @@ impl NumericSearchTerm {
         None
     }
 
-    fn compare_single(&self, json_num: f64) -> bool {
+    fn compare_single(&self, json_num: f64, rel_epsilon: f64) -> bool {
         match self {
             NumericSearchTerm::SingleComparison(op, target_num) => match op {
                 ComparisonOperator::GreaterThan => json_num > *target_num,
                 ComparisonOperator::LessThan => json_num < *target_num,
                 ComparisonOperator::GreaterThanOrEqual => json_num >= *target_num,
                 ComparisonOperator::LessThanOrEqual => json_num <= *target_num,
-                ComparisonOperator::Equal => json_num == *target_num,
+                ComparisonOperator::Equal => {
+                    json_num == *target_num
+                        || (json_num - target_num).abs()
+                            <= rel_epsilon * json_num.abs().max(target_num.abs())
+                }
             },
             _ => false,
         }
     }
 
-    fn compare_range(&self, json_num: f64) -> bool {
+    fn compare_range(&self, json_num: f64, rel_epsilon: f64) -> bool {
         match self {
             NumericSearchTerm::RangeComparison(op1, num1, op2, num2) => {
-                NumericSearchTerm::SingleComparison(op1.clone(), *num1).compare_single(json_num)
+                NumericSearchTerm::SingleComparison(op1.clone(), *num1)
+                    .compare_single(json_num, rel_epsilon)
                     && NumericSearchTerm::SingleComparison(op2.clone(), *num2)
-                        .compare_single(json_num)
+                        .compare_single(json_num, rel_epsilon)
             }
             _ => false,
         }
     }
 
-    pub fn matches(&self, json_num: f64) -> bool {
+    /// Checks the number against the term. `==` also accepts numbers differing by at most
+    /// `rel_epsilon` times the larger magnitude, so 0.0 means exact equality.
+    pub fn matches(&self, json_num: f64, rel_epsilon: f64) -> bool {
         match self {
-            NumericSearchTerm::SingleComparison(_, _) => self.compare_single(json_num),
-            NumericSearchTerm::RangeComparison(_, _, _, _) => self.compare_range(json_num),
+            NumericSearchTerm::SingleComparison(_, _) => self.compare_single(json_num, rel_epsilon),
+            NumericSearchTerm::RangeComparison(_, _, _, _) => {
+                self.compare_range(json_num, rel_epsilon)
+            }
         }
     }
 }
@@ -141,6 +607,8 @@ impl NumericSearchTerm {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use regex::Regex;
+    use serde_json::{json, Value};
 
     #[test]
     fn test_parse_search_path_valid_with_path() {
@@ -218,6 +686,93 @@ mod tests {
         assert_eq!(result, Ok((vec!["a", "b", "c"], "field")));
     }
 
+    #[test]
+    fn test_parse_path_parts() {
+        assert_eq!(parse_path_parts("a.b", "."), vec!["a", "b"]);
+        assert_eq!(parse_path_parts("a/0", "/"), vec!["a", "0"]);
+        assert_eq!(parse_path_parts("", "."), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_whole_value_regex() {
+        let is_match = |pattern: &str, value: Value| {
+            let regex = Regex::new(&whole_value_regex(pattern)).unwrap();
+            regex.is_match(&value.to_string())
+        };
+        assert!(is_match("test", json!("test")));
+        assert!(!is_match("test", json!("mytestvalue")));
+        assert!(is_match("^test$", json!("test")));
+        assert!(is_match("a|b", json!("b")));
+        assert!(!is_match("a|b", json!("ab")));
+        assert!(is_match(r"cost \$", json!("cost $")));
+        assert!(!is_match(r"cost \$", json!("cost $5")));
+        // Numbers are matched by their JSON text
+        assert!(is_match("30", json!(30)));
+        assert!(!is_match("30", json!(300)));
+        assert!(!is_match("1", json!(1.0)));
+        assert!(is_match(r"1\.0", json!(1.0)));
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let regex = Regex::new(&glob_to_regex("user_*")).unwrap();
+        assert!(regex.is_match(&json!("user_name").to_string()));
+        assert!(regex.is_match(&json!("user_").to_string()));
+        assert!(!regex.is_match(&json!("xuser_name").to_string()));
+        assert!(!regex.is_match(&json!("name_user_").to_string()));
+
+        let regex = Regex::new(&glob_to_regex("4?")).unwrap();
+        assert!(regex.is_match(&json!(42).to_string()));
+        assert!(!regex.is_match(&json!(4).to_string()));
+        assert!(!regex.is_match(&json!(420).to_string()));
+
+        let regex = Regex::new(&glob_to_regex("a.b")).unwrap();
+        assert!(regex.is_match(&json!("a.b").to_string()));
+        assert!(!regex.is_match(&json!("axb").to_string()));
+    }
+
+    #[test]
+    fn test_normalization_form() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_ne!(composed, decomposed);
+        for form in [NormalizationForm::Nfc, NormalizationForm::Nfd] {
+            assert_eq!(form.normalize(composed), form.normalize(decomposed));
+        }
+        assert_eq!(NormalizationForm::Nfc.normalize(decomposed), composed);
+        assert_eq!(NormalizationForm::Nfd.normalize(composed), decomposed);
+    }
+
+    #[test]
+    fn test_aggregate_function_apply() {
+        let numbers = [3.0, -1.5, 10.0];
+        assert_eq!(AggregateFunction::Sum.apply(&numbers), 11.5);
+        assert_eq!(AggregateFunction::Min.apply(&numbers), -1.5);
+        assert_eq!(AggregateFunction::Max.apply(&numbers), 10.0);
+    }
+
+    #[test]
+    fn test_json_type_matches() {
+        assert!(JsonType::String.matches(&json!("a")));
+        assert!(!JsonType::String.matches(&json!(1)));
+        assert!(JsonType::Number.matches(&json!(1.5)));
+        assert!(JsonType::Boolean.matches(&json!(false)));
+        assert!(JsonType::Null.matches(&json!(null)));
+        assert!(JsonType::Object.matches(&json!({})));
+        assert!(JsonType::Array.matches(&json!([])));
+        assert!(!JsonType::Array.matches(&json!({})));
+    }
+
+    #[test]
+    fn test_number_kind_matches() {
+        let int_number = serde_json::Number::from(30);
+        let float_number = serde_json::Number::from_f64(30.5).unwrap();
+        assert!(NumberKind::Int.matches(&int_number));
+        assert!(!NumberKind::Int.matches(&float_number));
+        assert!(NumberKind::Float.matches(&float_number));
+        assert!(!NumberKind::Float.matches(&int_number));
+    }
+
     #[test]
     fn test_parse_numeric_search_term_valid() {
         assert_eq!(
@@ -250,6 +805,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_numeric_search_term_relative_epsilon() {
+        let term = NumericSearchTerm::from_search_term("==1000000").unwrap();
+        assert!(term.matches(1_000_000.0, 0.0));
+        assert!(!term.matches(1_000_500.0, 0.0));
+        // Within 0.1% of the larger magnitude
+        assert!(term.matches(1_000_500.0, 0.001));
+        assert!(term.matches(999_001.0, 0.001));
+        assert!(!term.matches(1_001_500.0, 0.001));
+        assert!(!term.matches(998_000.0, 0.001));
+
+        let term = NumericSearchTerm::from_search_term(">1000000").unwrap();
+        assert!(!term.matches(999_999.0, 0.001));
+    }
+
+    #[test]
+    fn test_parse_numeric_search_term_suffixes() {
+        assert_eq!(
+            NumericSearchTerm::from_search_term(">10k"),
+            Some(NumericSearchTerm::SingleComparison(
+                ComparisonOperator::GreaterThan,
+                10_000.0
+            ))
+        );
+        assert_eq!(
+            NumericSearchTerm::from_search_term("<2M"),
+            Some(NumericSearchTerm::SingleComparison(
+                ComparisonOperator::LessThan,
+                2_000_000.0
+            ))
+        );
+        assert_eq!(
+            NumericSearchTerm::from_search_term("==1.5g"),
+            Some(NumericSearchTerm::SingleComparison(
+                ComparisonOperator::Equal,
+                1_500_000_000.0
+            ))
+        );
+        assert_eq!(
+            NumericSearchTerm::from_search_term(">=1K<=2m"),
+            Some(NumericSearchTerm::RangeComparison(
+                ComparisonOperator::GreaterThanOrEqual,
+                1_000.0,
+                ComparisonOperator::LessThanOrEqual,
+                2_000_000.0
+            ))
+        );
+        assert_eq!(
+            NumericSearchTerm::from_search_term(">0.5G<3g"),
+            Some(NumericSearchTerm::RangeComparison(
+                ComparisonOperator::GreaterThan,
+                500_000_000.0,
+                ComparisonOperator::LessThan,
+                3_000_000_000.0
+            ))
+        );
+        assert_eq!(NumericSearchTerm::from_search_term(">10x"), None);
+        assert_eq!(NumericSearchTerm::from_search_term(">k"), None);
+        assert_eq!(NumericSearchTerm::from_search_term(">10kk"), None);
+    }
+
     #[test]
     fn test_parse_numeric_search_term_invalid() {
         assert_eq!(NumericSearchTerm::from_search_term("!=10"), None);
@@ -336,4 +952,208 @@ mod tests {
         assert_eq!(NumericSearchTerm::from_search_term(">="), None);
         assert_eq!(NumericSearchTerm::from_search_term("<="), None);
     }
+
+    #[test]
+    fn test_resolve_search_path_split_form() {
+        for search_path in ["a.b.c", "c"] {
+            let (in_path, field) = search_path.rsplit_once('.').unwrap_or(("", search_path));
+            assert_eq!(
+                resolve_search_path("", Some(in_path), Some(field), "."),
+                parse_search_path(search_path, "."),
+            );
+        }
+        assert_eq!(
+            resolve_search_path("", None, Some("c"), "."),
+            Ok((vec![], "c"))
+        );
+        // The field name is taken as is, separator included
+        assert_eq!(
+            resolve_search_path("", Some("a"), Some("b.c"), "."),
+            Ok((vec!["a"], "b.c"))
+        );
+        assert_eq!(
+            resolve_search_path("a.b", None, None, "."),
+            Ok((vec!["a"], "b"))
+        );
+        assert!(resolve_search_path("", Some("a"), Some(""), ".").is_err());
+    }
+
+    #[test]
+    fn test_split_numeric_prefix() {
+        assert_eq!(split_numeric_prefix("num:>30"), (">30", true));
+        assert_eq!(
+            NumericSearchTerm::from_search_term(split_numeric_prefix("num:>30").0),
+            NumericSearchTerm::from_search_term(">30"),
+        );
+        assert_eq!(split_numeric_prefix(">30"), (">30", false));
+        assert_eq!(split_numeric_prefix("number:>30"), ("number:>30", false));
+    }
+
+    #[test]
+    fn test_parse_formatted_number() {
+        assert_eq!(parse_formatted_number("$1,200.50"), Some(1200.5));
+        assert_eq!(parse_formatted_number("-€ 3 000"), Some(-3000.0));
+        assert_eq!(parse_formatted_number("£-7"), Some(-7.0));
+        assert_eq!(parse_formatted_number("45%"), Some(0.45));
+        assert_eq!(parse_formatted_number(" 12 "), Some(12.0));
+        assert_eq!(parse_formatted_number("1e3"), None);
+        assert_eq!(parse_formatted_number("N/A"), None);
+        assert_eq!(parse_formatted_number("$"), None);
+        assert_eq!(parse_formatted_number("%"), None);
+    }
+
+    #[test]
+    fn test_explain_search() {
+        let (field_path_parts, field_name) = parse_search_path("a.b.c", ".").unwrap();
+        assert_eq!(
+            explain_search(&field_path_parts, field_name, ">=1<10k", true),
+            vec![
+                "field_path_parts: [\"a\", \"b\"]",
+                "field_name: \"c\"",
+                "numeric_term: >=1<10000",
+                "regex: \">=1<10k\"",
+            ]
+        );
+        assert_eq!(explain_search(&[], "c", ">5", true)[2], "numeric_term: >5");
+        assert_eq!(
+            explain_search(&[], "c", ">2.5", true)[2],
+            "numeric_term: >2.5"
+        );
+        // A term that looks numeric is matched as a regex without numeric search
+        assert_eq!(
+            explain_search(&[], "c", ">5", false)[2],
+            "numeric_term: none"
+        );
+        assert_eq!(
+            explain_search(&[], "c", "^x$", true)[2],
+            "numeric_term: none"
+        );
+    }
+
+    #[test]
+    fn test_divisibility() {
+        let number = |value: serde_json::Value| value.as_number().unwrap().clone();
+        let divisible_by_3 = Divisibility::divisible_by(3);
+        assert!(divisible_by_3.matches(&number(json!(9))));
+        assert!(divisible_by_3.matches(&number(json!(0))));
+        assert!(divisible_by_3.matches(&number(json!(-12))));
+        assert!(!divisible_by_3.matches(&number(json!(10))));
+        assert!(!divisible_by_3.matches(&number(json!(9.0))));
+        assert!(Divisibility::divisible_by(7).matches(&number(json!(u64::MAX - 1))));
+
+        assert!(Divisibility::odd().matches(&number(json!(3))));
+        assert!(Divisibility::odd().matches(&number(json!(-3))));
+        assert!(!Divisibility::odd().matches(&number(json!(4))));
+        assert!(Divisibility::even().matches(&number(json!(-4))));
+        assert!(!Divisibility::even().matches(&number(json!(3))));
+        assert!(!Divisibility::even().matches(&number(json!(2.5))));
+    }
+
+    #[test]
+    fn test_int_range() {
+        let number = |value: serde_json::Value| value.as_number().unwrap().clone();
+        let exclusive = IntRange::from_range("1..100").unwrap();
+        assert!(exclusive.matches(&number(json!(1))));
+        assert!(exclusive.matches(&number(json!(99))));
+        assert!(!exclusive.matches(&number(json!(100))));
+        assert!(!exclusive.matches(&number(json!(0))));
+        assert!(!exclusive.matches(&number(json!(50.5))));
+        assert!(!exclusive.matches(&number(json!(50.0))));
+
+        let inclusive = IntRange::from_range("-5..=100").unwrap();
+        assert!(inclusive.matches(&number(json!(100))));
+        assert!(inclusive.matches(&number(json!(-5))));
+        assert!(!inclusive.matches(&number(json!(101))));
+
+        let open = IntRange::from_range("10..").unwrap();
+        assert!(open.matches(&number(json!(u64::MAX))));
+        assert!(!open.matches(&number(json!(9))));
+
+        assert!(IntRange::from_range("1-100").is_err());
+        assert!(IntRange::from_range("1..x").is_err());
+        assert!(IntRange::from_range("1..=").is_err());
+    }
+
+    #[test]
+    fn test_epoch_search_term() {
+        let matches = |search_term: &str, milliseconds, timestamp: Value| {
+            let epoch_term = EpochSearchTerm::from_search_term(search_term, milliseconds).unwrap();
+            epoch_term.matches(timestamp.as_number().unwrap())
+        };
+        // 2024-01-01T00:00:00Z and one second before
+        assert!(matches(">=2024-01-01T00:00:00Z", false, json!(1704067200)));
+        assert!(!matches(">=2024-01-01T00:00:00Z", false, json!(1704067199)));
+        assert!(matches("<2024-01-01T00:00:00Z", false, json!(1704067199.5)));
+        assert!(matches(
+            "2024-01-01T00:00:00Z",
+            true,
+            json!(1704067200000_u64)
+        ));
+        assert!(!matches(
+            "2024-01-01T00:00:00Z",
+            false,
+            json!(1704067200000_u64)
+        ));
+        assert!(EpochSearchTerm::from_search_term(">=yesterday", false).is_err());
+    }
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(
+            LineRange::from_range("120:180"),
+            Ok(LineRange {
+                start: 120,
+                end: 180
+            })
+        );
+        assert_eq!(
+            LineRange::from_range("3:3"),
+            Ok(LineRange { start: 3, end: 3 })
+        );
+        assert!(LineRange::from_range("0:5").is_err());
+        assert!(LineRange::from_range("5:4").is_err());
+        assert!(LineRange::from_range("5").is_err());
+        assert!(LineRange::from_range("a:b").is_err());
+    }
+
+    #[test]
+    fn test_version_search_term() {
+        let version_term = VersionSearchTerm::from_search_term(">=1.2.0").unwrap();
+        assert!(version_term.matches("1.2.0"));
+        assert!(version_term.matches("1.10.0"));
+        assert!(!version_term.matches("1.1.9"));
+        assert!(!version_term.matches("not a version"));
+        assert!(!version_term.matches("1.2"));
+
+        // Naive string comparison would order 1.10.0 before 1.9.0
+        let version_term = VersionSearchTerm::from_search_term(">1.9.0").unwrap();
+        assert!(version_term.matches("1.10.0"));
+        assert!("1.10.0" < "1.9.0");
+
+        let version_term = VersionSearchTerm::from_search_term("2.0.0-rc.1").unwrap();
+        assert!(version_term.matches("2.0.0-rc.1"));
+        assert!(!version_term.matches("2.0.0"));
+
+        assert!(VersionSearchTerm::from_search_term(">=1.x").is_err());
+        assert!(VersionSearchTerm::from_search_term("").is_err());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let timestamp = parse_date("2024-05-01T12:00:00Z").unwrap();
+        let since_epoch = timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+        assert_eq!(since_epoch.as_secs(), 1_714_564_800);
+        assert_eq!(parse_date("2024-05-01T14:00:00+02:00"), Ok(timestamp));
+
+        let day = parse_date("2024-05-01").unwrap();
+        let local_midnight = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_time(NaiveTime::MIN)
+            .and_local_timezone(Local)
+            .unwrap();
+        assert_eq!(day, SystemTime::from(local_midnight));
+
+        assert!(parse_date("yesterday").is_err());
+        assert!(parse_date("2024-13-01").is_err());
+    }
 }
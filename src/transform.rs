@@ -0,0 +1,278 @@
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::parse::{search_json_value, to_json_pointer, SearchContext};
+
+pub enum Transform<'a> {
+    /// Regex replacement applied to matched string values.
+    Replace(&'a str),
+    /// Removal of matched object fields and array elements.
+    Delete,
+}
+
+/// Orders paths segment by segment, comparing array indices numerically.
+fn compare_paths(path_a: &[String], path_b: &[String]) -> Ordering {
+    for (part_a, part_b) in path_a.iter().zip(path_b) {
+        let ordering = match (part_a.parse::<usize>(), part_b.parse::<usize>()) {
+            (Ok(index_a), Ok(index_b)) => index_a.cmp(&index_b),
+            _ => part_a.cmp(part_b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    path_a.len().cmp(&path_b.len())
+}
+
+fn delete_path(json_value: &mut Value, path: &[String]) {
+    let Some((last_part, parent_path)) = path.split_last() else {
+        return;
+    };
+    match json_value.pointer_mut(&to_json_pointer(parent_path)) {
+        Some(Value::Object(obj)) => {
+            obj.remove(last_part);
+        }
+        Some(Value::Array(arr)) => {
+            if let Ok(index) = last_part.parse::<usize>() {
+                if index < arr.len() {
+                    arr.remove(index);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_transform(
+    json_value: &mut Value,
+    mut matched_paths: Vec<Vec<String>>,
+    transform: &Transform,
+    search_context: &SearchContext,
+) {
+    match transform {
+        Transform::Replace(replacement) => {
+            for path in matched_paths {
+                // Numbers, booleans and containers are left untouched
+                if let Some(Value::String(string_value)) =
+                    json_value.pointer_mut(&to_json_pointer(&path))
+                {
+                    *string_value = search_context
+                        .search_regex
+                        .replace_all(string_value, *replacement)
+                        .into_owned();
+                }
+            }
+        }
+        Transform::Delete => {
+            // Deleting from the back keeps the indices of the remaining matches valid
+            matched_paths.sort_by(|path_a, path_b| compare_paths(path_b, path_a));
+            for path in matched_paths {
+                delete_path(json_value, &path);
+            }
+        }
+    }
+}
+
+pub fn process_json_transform(
+    json_input_raw: String,
+    field_path_parts: &[&str],
+    field_name: &str,
+    transform: &Transform,
+    search_context: &SearchContext,
+) -> Result<String, serde_json::Error> {
+    let mut json_value = serde_json::from_str::<Value>(&json_input_raw)?;
+    let root_path: Vec<String> = search_context
+        .root_path_parts
+        .iter()
+        .map(|part| part.to_string())
+        .collect();
+    let matched_paths: Vec<Vec<String>> = json_value
+        .pointer(&to_json_pointer(&root_path))
+        .and_then(|root_value| {
+            search_json_value(
+                root_value,
+                field_path_parts,
+                field_name,
+                Vec::new(),
+                search_context,
+            )
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .map(|result| [root_path.clone(), result.json_path].concat())
+        .collect();
+    // The whole document is emitted, even if only the subtree at the root is modified
+    apply_transform(&mut json_value, matched_paths, transform, search_context);
+    serde_json::to_string_pretty(&json_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use serde_json::json;
+
+    fn transform_json(
+        json_value: &Value,
+        field_path_parts: &[&str],
+        field_name: &str,
+        transform: &Transform,
+        search_context: &SearchContext,
+    ) -> Value {
+        let output = process_json_transform(
+            json_value.to_string(),
+            field_path_parts,
+            field_name,
+            transform,
+            search_context,
+        )
+        .unwrap();
+        serde_json::from_str(&output).unwrap()
+    }
+
+    #[test]
+    fn test_process_json_transform_replace_string_values() {
+        let json_value = json!({
+            "user": {"name": "Max", "nickname": "Max"},
+            "users": [{"name": "Maxine"}, {"name": "Anna"}]
+        });
+        let search_regex = Regex::new("Max").unwrap();
+        let transformed = transform_json(
+            &json_value,
+            &[],
+            "name",
+            &Transform::Replace("Maximilian"),
+            &SearchContext::with_defaults(&search_regex),
+        );
+        assert_eq!(
+            transformed,
+            json!({
+                "user": {"name": "Maximilian", "nickname": "Max"},
+                "users": [{"name": "Maximilianine"}, {"name": "Anna"}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_json_transform_replace_with_path_and_capture_group() {
+        let json_value = json!({
+            "a": {"id": "user-1"},
+            "b": {"id": "user-2"}
+        });
+        let search_regex = Regex::new("user-(\\d+)").unwrap();
+        let transformed = transform_json(
+            &json_value,
+            &["a"],
+            "id",
+            &Transform::Replace("account-$1"),
+            &SearchContext::with_defaults(&search_regex),
+        );
+        assert_eq!(
+            transformed,
+            json!({
+                "a": {"id": "account-1"},
+                "b": {"id": "user-2"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_json_transform_replace_skips_non_string_values() {
+        let json_value = json!({
+            "a": [{"value": 1}, {"value": true}, {"value": {"value": "1"}}, {"value": [1]}]
+        });
+        let search_regex = Regex::new("1|true").unwrap();
+        let transformed = transform_json(
+            &json_value,
+            &[],
+            "value",
+            &Transform::Replace("x"),
+            &SearchContext::with_defaults(&search_regex),
+        );
+        assert_eq!(
+            transformed,
+            json!({
+                "a": [{"value": 1}, {"value": true}, {"value": {"value": "x"}}, {"value": [1]}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_json_transform_delete_key() {
+        let json_value = json!({
+            "users": [{"name": "Max", "debug": true}, {"name": "Anna", "debug": false}],
+            "debug": true
+        });
+        let search_regex = Regex::new("true").unwrap();
+        let transformed = transform_json(
+            &json_value,
+            &[],
+            "debug",
+            &Transform::Delete,
+            &SearchContext::with_defaults(&search_regex),
+        );
+        assert_eq!(
+            transformed,
+            json!({
+                "users": [{"name": "Max"}, {"name": "Anna", "debug": false}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_json_transform_delete_array_elements() {
+        let json_value = json!({
+            "a": [["x", "y"], ["x", "x"], ["y", "x"]]
+        });
+        let search_regex = Regex::new("x").unwrap();
+        let transformed = transform_json(
+            &json_value,
+            &["a"],
+            "0",
+            &Transform::Delete,
+            &SearchContext::with_defaults(&search_regex),
+        );
+        assert_eq!(
+            transformed,
+            json!({
+                "a": [["y"], ["x"], ["y", "x"]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_compare_paths_numeric_indices() {
+        let path = |parts: &[&str]| {
+            parts
+                .iter()
+                .map(|part| part.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            compare_paths(&path(&["a", "2"]), &path(&["a", "10"])),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_paths(&path(&["a"]), &path(&["a", "0"])),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_paths(&path(&["b"]), &path(&["a", "0"])),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_process_json_transform_invalid_json() {
+        let search_regex = Regex::new("test").unwrap();
+        let output = process_json_transform(
+            r#"{invalid json"#.to_string(),
+            &[],
+            "a",
+            &Transform::Delete,
+            &SearchContext::with_defaults(&search_regex),
+        );
+        assert!(output.is_err());
+    }
+}
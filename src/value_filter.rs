@@ -0,0 +1,309 @@
+use serde_json::Value;
+
+use crate::syntax::ComparisonOperator;
+
+/// Expression of `--jq-value`, a small subset of jq: `.` for the value, JSON literals,
+/// the comparisons `==`, `!=`, `<`, `<=`, `>`, `>=`, `and`, `or` and parentheses.
+/// `and` binds tighter than `or`, e.g. `. < 0 or . > 10 and . < 20`.
+#[derive(Debug, PartialEq)]
+pub enum ValueFilter {
+    Value,
+    Literal(Value),
+    Compare(Box<ValueFilter>, ComparisonOperator, Box<ValueFilter>),
+    NotEqual(Box<ValueFilter>, Box<ValueFilter>),
+    And(Box<ValueFilter>, Box<ValueFilter>),
+    Or(Box<ValueFilter>, Box<ValueFilter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Literal(Value),
+    Operator(String),
+    And,
+    Or,
+    OpenParen,
+    CloseParen,
+}
+
+impl ValueFilter {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let value_filter = parser.parse_or()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(value_filter),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    /// Whether the expression is truthy for the value. As in jq, only false and null are falsy.
+    pub fn matches(&self, value: &Value) -> bool {
+        !matches!(self.evaluate(value), Value::Null | Value::Bool(false))
+    }
+
+    fn evaluate(&self, value: &Value) -> Value {
+        match self {
+            ValueFilter::Value => value.clone(),
+            ValueFilter::Literal(literal) => literal.clone(),
+            ValueFilter::Compare(left, operator, right) => Value::Bool(compare_values(
+                &left.evaluate(value),
+                operator,
+                &right.evaluate(value),
+            )),
+            ValueFilter::NotEqual(left, right) => Value::Bool(!compare_values(
+                &left.evaluate(value),
+                &ComparisonOperator::Equal,
+                &right.evaluate(value),
+            )),
+            ValueFilter::And(left, right) => {
+                Value::Bool(left.matches(value) && right.matches(value))
+            }
+            ValueFilter::Or(left, right) => {
+                Value::Bool(left.matches(value) || right.matches(value))
+            }
+        }
+    }
+}
+
+/// Numbers compare numerically and strings lexicographically. Other values are only equal to
+/// themselves, and values of different types are never ordered.
+fn compare_values(left: &Value, operator: &ComparisonOperator, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => match (left.as_f64(), right.as_f64()) {
+            (Some(left), Some(right)) => operator.compare(&left, &right),
+            _ => false,
+        },
+        (Value::String(left), Value::String(right)) => operator.compare(left, right),
+        _ => matches!(operator, ComparisonOperator::Equal) && left == right,
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '.' => {
+                chars.next();
+                tokens.push(Token::Dot);
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' {
+                    Token::OpenParen
+                } else {
+                    Token::CloseParen
+                });
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let mut operator = c.to_string();
+                if chars.next_if(|&(_, next)| next == '=').is_some() {
+                    operator.push('=');
+                }
+                if operator == "=" || operator == "!" {
+                    return Err(format!("unknown operator '{}'", operator));
+                }
+                tokens.push(Token::Operator(operator));
+            }
+            '"' => {
+                chars.next();
+                let mut end = None;
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            end = Some(i + 1);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                let end = end.ok_or("unterminated string")?;
+                let literal = serde_json::from_str(&expression[start..end])
+                    .map_err(|e| format!("invalid string {}: {}", &expression[start..end], e))?;
+                tokens.push(Token::Literal(literal));
+            }
+            _ => {
+                let mut end = start;
+                while let Some((i, c)) = chars
+                    .next_if(|&(_, c)| c.is_alphanumeric() || matches!(c, '-' | '+' | '.' | '_'))
+                {
+                    end = i + c.len_utf8();
+                }
+                let word = &expression[start..end];
+                tokens.push(match word {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "" => return Err(format!("unexpected '{}'", c)),
+                    _ => match serde_json::from_str::<Value>(word) {
+                        Ok(literal @ (Value::Number(_) | Value::Bool(_) | Value::Null)) => {
+                            Token::Literal(literal)
+                        }
+                        _ => return Err(format!("unknown word '{}'", word)),
+                    },
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        let is_expected = self.tokens.get(self.position) == Some(expected);
+        if is_expected {
+            self.position += 1;
+        }
+        is_expected
+    }
+
+    fn parse_or(&mut self) -> Result<ValueFilter, String> {
+        let mut left = self.parse_and()?;
+        while self.next_if(&Token::Or) {
+            left = ValueFilter::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<ValueFilter, String> {
+        let mut left = self.parse_comparison()?;
+        while self.next_if(&Token::And) {
+            left = ValueFilter::And(Box::new(left), Box::new(self.parse_comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_comparison(&mut self) -> Result<ValueFilter, String> {
+        let left = self.parse_operand()?;
+        let Some(Token::Operator(operator)) = self.tokens.get(self.position) else {
+            return Ok(left);
+        };
+        self.position += 1;
+        let right = Box::new(self.parse_operand()?);
+        Ok(match ComparisonOperator::from_str(operator) {
+            Some(operator) => ValueFilter::Compare(Box::new(left), operator, right),
+            // The tokenizer only produces the comparisons and `!=`
+            None => ValueFilter::NotEqual(Box::new(left), right),
+        })
+    }
+
+    fn parse_operand(&mut self) -> Result<ValueFilter, String> {
+        match self.next() {
+            Some(Token::Dot) => Ok(ValueFilter::Value),
+            Some(Token::Literal(literal)) => Ok(ValueFilter::Literal(literal.clone())),
+            Some(Token::OpenParen) => {
+                let inner = self.parse_or()?;
+                if self.next_if(&Token::CloseParen) {
+                    Ok(inner)
+                } else {
+                    Err("missing ')'".to_string())
+                }
+            }
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn matching(expression: &str, values: &[Value]) -> Vec<Value> {
+        let value_filter = ValueFilter::parse(expression).unwrap();
+        values
+            .iter()
+            .filter(|value| value_filter.matches(value))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_value_filter_conjunction() {
+        let values = [
+            json!(18),
+            json!(30),
+            json!(31),
+            json!(64.5),
+            json!(65),
+            json!("40"),
+        ];
+        assert_eq!(
+            matching(". > 30 and . < 65", &values),
+            vec![json!(31), json!(64.5)]
+        );
+    }
+
+    #[test]
+    fn test_value_filter_disjunction() {
+        let values = [
+            json!("admin"),
+            json!("root"),
+            json!("guest"),
+            json!(null),
+            json!(true),
+        ];
+        assert_eq!(
+            matching(r#". == "admin" or . == "root" or . == true"#, &values),
+            vec![json!("admin"), json!("root"), json!(true)]
+        );
+        // `and` binds tighter than `or`
+        let values = [json!(-1), json!(5), json!(15), json!(25)];
+        assert_eq!(
+            matching(". < 0 or . > 10 and . < 20", &values),
+            vec![json!(-1), json!(15)]
+        );
+        assert_eq!(
+            matching("(. < 0 or . > 10) and . != 25", &values),
+            vec![json!(-1), json!(15)]
+        );
+    }
+
+    #[test]
+    fn test_value_filter_literals() {
+        assert!(ValueFilter::parse(". == null")
+            .unwrap()
+            .matches(&json!(null)));
+        assert!(ValueFilter::parse(".").unwrap().matches(&json!(0)));
+        assert!(!ValueFilter::parse(".").unwrap().matches(&json!(false)));
+        assert!(ValueFilter::parse(r#". >= "b\"""#)
+            .unwrap()
+            .matches(&json!("c")));
+        assert!(ValueFilter::parse("1e3 == 1000")
+            .unwrap()
+            .matches(&json!(0)));
+    }
+
+    #[test]
+    fn test_value_filter_parse_errors() {
+        assert!(ValueFilter::parse("").is_err());
+        assert!(ValueFilter::parse(". >").is_err());
+        assert!(ValueFilter::parse(". = 1").is_err());
+        assert!(ValueFilter::parse("(. > 1").is_err());
+        assert!(ValueFilter::parse(". > 1 2").is_err());
+        assert!(ValueFilter::parse(". > abc").is_err());
+        assert!(ValueFilter::parse(r#". == "open"#).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+use notify_debouncer_mini::notify::{self, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+
+/// Rapid successive events, e.g. an editor truncating and then writing a file, trigger one search.
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(200);
+
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+struct FileWatcher {
+    // Stops watching when dropped
+    _debouncer: Debouncer<RecommendedWatcher>,
+    events: Receiver<DebounceEventResult>,
+    modified_times: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+/// None if the file does not exist, e.g. between an editor's delete and rename.
+fn modified_time(file_path: &Path) -> Option<SystemTime> {
+    fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl FileWatcher {
+    /// Watches the directories of the files, so that files replaced by a rename are still noticed.
+    fn new(json_files: &[String]) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, sender)?;
+        let watched_files = json_files
+            .iter()
+            .map(fs::canonicalize)
+            .collect::<io::Result<HashSet<_>>>()?;
+        let watched_dirs: HashSet<_> = watched_files
+            .iter()
+            .filter_map(|file_path| file_path.parent())
+            .collect();
+        for dir_path in watched_dirs {
+            debouncer
+                .watcher()
+                .watch(dir_path, RecursiveMode::NonRecursive)?;
+        }
+        let modified_times = watched_files
+            .into_iter()
+            .map(|file_path| {
+                let modified_time = modified_time(&file_path);
+                (file_path, modified_time)
+            })
+            .collect();
+        Ok(FileWatcher {
+            _debouncer: debouncer,
+            events,
+            modified_times,
+        })
+    }
+
+    /// Blocks until one of the files changes. Returns false if the timeout passes first.
+    /// Events that leave the modification time unchanged, e.g. srch reading the file, are ignored.
+    fn wait_for_change(&mut self, timeout: Option<Duration>) -> notify::Result<bool> {
+        loop {
+            let event_result = match timeout {
+                Some(timeout) => match self.events.recv_timeout(timeout) {
+                    Ok(event_result) => event_result,
+                    Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+                        return Ok(false)
+                    }
+                },
+                None => match self.events.recv() {
+                    Ok(event_result) => event_result,
+                    Err(_) => return Ok(false),
+                },
+            };
+            let mut has_changed = false;
+            for event in event_result? {
+                if let Some(previous_time) = self.modified_times.get_mut(&event.path) {
+                    let current_time = modified_time(&event.path);
+                    has_changed |= *previous_time != current_time;
+                    *previous_time = current_time;
+                }
+            }
+            if has_changed {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+/// Runs the search, then again whenever one of the files changes, until interrupted.
+/// The screen is cleared before each run unless `clear_screen` is false.
+pub fn watch_files(
+    json_files: &[String],
+    clear_screen: bool,
+    mut search: impl FnMut(),
+) -> notify::Result<()> {
+    let mut file_watcher = FileWatcher::new(json_files)?;
+    loop {
+        if clear_screen {
+            print!("{}", CLEAR_SCREEN);
+        }
+        search();
+        io::stdout().flush()?;
+        if !file_watcher.wait_for_change(None)? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    /// Writes the file and sets its modification time explicitly, so a test neither depends on
+    /// the timestamp resolution of the file system nor has to sleep between writes.
+    fn write_with_modified_time(file_path: &Path, contents: &str, modified_time: SystemTime) {
+        fs::write(file_path, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(file_path)
+            .unwrap()
+            .set_modified(modified_time)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_file_modification_triggers_change() {
+        let dir_path = std::env::temp_dir().join(format!(
+            "srch_test_file_modification_triggers_change_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir_path).unwrap();
+        let file_path = dir_path.join("watched.json");
+        let other_path = dir_path.join("other.json");
+        let initial_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        write_with_modified_time(&file_path, r#"{"a": 1}"#, initial_time);
+        let mut file_watcher =
+            FileWatcher::new(&[file_path.to_string_lossy().to_string()]).unwrap();
+        let timeout = Some(Duration::from_secs(1));
+
+        // Other files in the directory and events that keep the modification time are ignored
+        fs::write(&other_path, r#"{"a": 2}"#).unwrap();
+        fs::read_to_string(&file_path).unwrap();
+        write_with_modified_time(&file_path, r#"{"a": 1}"#, initial_time);
+        assert!(!file_watcher.wait_for_change(timeout).unwrap());
+
+        write_with_modified_time(
+            &file_path,
+            r#"{"a": 3}"#,
+            initial_time + Duration::from_secs(1),
+        );
+        let has_changed = file_watcher
+            .wait_for_change(Some(Duration::from_secs(5)))
+            .unwrap();
+        fs::remove_dir_all(&dir_path).unwrap();
+        assert!(has_changed);
+    }
+}
@@ -0,0 +1,54 @@
+//! The SRCH_* environment variables are set on a child process only, so the
+//! environment of the parallel unit tests stays untouched.
+use std::process::{Command, Output};
+
+fn srch_with_env(env: &[(&str, &str)], args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_srch"))
+        .envs(env.iter().copied())
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_separator_from_environment() {
+    let json = r#"{"a": {"b": "x"}}"#;
+    let env_output = srch_with_env(&[("SRCH_SEPARATOR", "/")], &["a/b", "x", "-j", json]);
+    assert_eq!(stdout(&env_output), "a/b: \"x\"\n");
+    // Flags take precedence over the environment
+    let cli_output = srch_with_env(
+        &[("SRCH_SEPARATOR", "/")],
+        &["a_b", "x", "-f", "_", "-j", json],
+    );
+    assert_eq!(stdout(&cli_output), "a_b: \"x\"\n");
+}
+
+#[test]
+fn test_max_value_width_from_environment() {
+    let output = srch_with_env(
+        &[("SRCH_MAX_VALUE_WIDTH", "5")],
+        &["a", "x", "--table", "-j", r#"{"a": "xxxxxxxx"}"#],
+    );
+    assert_eq!(stdout(&output), "a  \"xxx…\n");
+}
+
+#[test]
+fn test_error_format_from_environment() {
+    let output = srch_with_env(&[("SRCH_ERROR_FORMAT", "json")], &["a", "x", "-j", "{bad"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with(r#"{"error":"#));
+    assert!(stderr.contains(r#""kind":"json_parse""#));
+}
+
+#[test]
+fn test_timeout_from_environment() {
+    let output = srch_with_env(&[("SRCH_TIMEOUT", "0")], &["a", "x", "-j", r#"{"a": "x"}"#]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("time limit exceeded"));
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::process::{Command, ExitStatus};
+
+fn srch(args: &[&str]) -> ExitStatus {
+    Command::new(env!("CARGO_BIN_EXE_srch"))
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+}
+
+#[test]
+fn test_exit_status_without_errors() {
+    assert!(srch(&["a", "x", "-j", r#"{"a": "x"}"#]).success());
+    assert!(srch(&["a", "y", "-j", r#"{"a": "x"}"#]).success());
+}
+
+#[test]
+fn test_exit_status_on_reported_errors() {
+    let dir_path = std::env::temp_dir().join("srch_test_exit_status_on_reported_errors");
+    fs::create_dir_all(&dir_path).unwrap();
+    let valid_path = dir_path.join("valid.json");
+    fs::write(&valid_path, r#"{"a": "x"}"#).unwrap();
+    let csv_path = dir_path.join("rows.csv");
+    fs::write(
+        &csv_path,
+        "id,payload\n1,\"{\"\"a\"\": \"\"x\"\"}\"\n2,{bad\n",
+    )
+    .unwrap();
+    let valid_path = valid_path.to_str().unwrap();
+    let missing_path = dir_path.join("missing.json");
+    let unwritable_path = dir_path.join("missing_dir").join("also.json");
+
+    let parse_error = srch(&["a", "x", "-j", "{bad", "--error-format", "json"]);
+    let missing_file = srch(&["a", "x", valid_path, missing_path.to_str().unwrap()]);
+    let unwritable_also = srch(&[
+        "a",
+        "x",
+        valid_path,
+        "--also",
+        "json",
+        unwritable_path.to_str().unwrap(),
+    ]);
+    let malformed_row = srch(&[
+        "a",
+        "x",
+        csv_path.to_str().unwrap(),
+        "--input",
+        "csv",
+        "--json-column",
+        "payload",
+    ]);
+    fs::remove_dir_all(&dir_path).unwrap();
+
+    assert_eq!(parse_error.code(), Some(2));
+    assert_eq!(missing_file.code(), Some(2));
+    assert_eq!(unwritable_also.code(), Some(2));
+    assert_eq!(malformed_row.code(), Some(2));
+}
+
+#[test]
+fn test_exit_status_fail_on_match() {
+    let json = r#"{"a": "x"}"#;
+    let matched = srch(&["a", "x", "-j", json, "--fail-on-match"]);
+    let not_matched = srch(&["a", "y", "-j", json, "--fail-on-match"]);
+    let failed = srch(&["a", "x", "-j", "{bad", "--fail-on-match"]);
+
+    assert_eq!(matched.code(), Some(1));
+    assert_eq!(not_matched.code(), Some(0));
+    // Errors keep their own code, so scripts can tell them from a match
+    assert_eq!(failed.code(), Some(2));
+}
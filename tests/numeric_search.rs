@@ -0,0 +1,53 @@
+use std::process::{Command, Output};
+
+fn srch(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_srch"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_all_with_numeric_prefix() {
+    let json = r#"{"people": [{"age": 17}, {"age": 30}, {"age": 70}]}"#;
+    let with_flag = srch(&["age", ">18", "-n", "--all", "<65", "-j", json]);
+    let with_prefix = srch(&["age", "num:>18", "--all", "<65", "-j", json]);
+    assert_eq!(
+        String::from_utf8(with_flag.stdout).unwrap(),
+        "people.1.age: 30\n"
+    );
+    assert_eq!(
+        String::from_utf8(with_prefix.stdout).unwrap(),
+        "people.1.age: 30\n"
+    );
+}
+
+#[test]
+fn test_rel_epsilon_with_numeric_prefix() {
+    let output = srch(&[
+        "a",
+        "num:==100",
+        "--rel-epsilon",
+        "0.01",
+        "-j",
+        r#"{"a": 100.5}"#,
+    ]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "a: 100.5\n");
+}
+
+#[test]
+fn test_numeric_options_require_numeric_search() {
+    let json = r#"{"age": 30}"#;
+    assert_eq!(
+        srch(&["age", ">18", "--all", "<65", "-j", json])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        srch(&["age", "30", "--rel-epsilon", "0.01", "-j", json])
+            .status
+            .code(),
+        Some(2)
+    );
+}